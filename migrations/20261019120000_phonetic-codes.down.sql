@@ -0,0 +1 @@
+DROP TABLE phonetic_codes;
@@ -0,0 +1,11 @@
+CREATE TABLE phonetic_codes
+(
+	name      TEXT NOT NULL
+		REFERENCES names (name)
+			ON DELETE CASCADE,
+	algorithm TEXT NOT NULL,
+	code      TEXT NOT NULL,
+	PRIMARY KEY (name, algorithm, code)
+);
+
+CREATE INDEX phonetic_codes_by_code ON phonetic_codes (algorithm, code);
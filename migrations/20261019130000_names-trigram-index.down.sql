@@ -0,0 +1,6 @@
+DROP TRIGGER names_trigram_update;
+DROP TRIGGER names_trigram_delete;
+DROP TRIGGER names_trigram_insert;
+
+DROP TABLE names_trigram;
+DROP TABLE names_trigram_keys;
@@ -0,0 +1,58 @@
+-- stable keys for the trigram index, the implicit rowid of names can change with a VACUUM
+CREATE TABLE names_trigram_keys
+(
+	id   INTEGER NOT NULL PRIMARY KEY,
+	name TEXT    NOT NULL UNIQUE
+);
+
+INSERT INTO names_trigram_keys(name)
+SELECT name
+FROM names;
+
+-- the index only stores the trigrams, the names themselves are read from names_trigram_keys
+CREATE VIRTUAL TABLE names_trigram USING fts5
+(
+	name,
+	content = 'names_trigram_keys',
+	content_rowid = 'id',
+	tokenize = 'trigram'
+);
+
+INSERT INTO names_trigram(names_trigram) VALUES ('rebuild');
+
+CREATE TRIGGER names_trigram_insert
+	AFTER INSERT
+	ON names
+BEGIN
+	INSERT INTO names_trigram_keys(name) VALUES (new.name);
+	INSERT INTO names_trigram(rowid, name)
+	SELECT id, name
+	FROM names_trigram_keys
+	WHERE name = new.name;
+END;
+
+CREATE TRIGGER names_trigram_delete
+	AFTER DELETE
+	ON names
+BEGIN
+	INSERT INTO names_trigram(names_trigram, rowid, name)
+	SELECT 'delete', id, name
+	FROM names_trigram_keys
+	WHERE name = old.name;
+	DELETE FROM names_trigram_keys WHERE name = old.name;
+END;
+
+CREATE TRIGGER names_trigram_update
+	AFTER UPDATE OF name
+	ON names
+BEGIN
+	INSERT INTO names_trigram(names_trigram, rowid, name)
+	SELECT 'delete', id, name
+	FROM names_trigram_keys
+	WHERE name = old.name;
+	UPDATE names_trigram_keys SET name = new.name WHERE name = old.name;
+	INSERT INTO names_trigram(rowid, name)
+	SELECT id, name
+	FROM names_trigram_keys
+	WHERE name = new.name;
+END;
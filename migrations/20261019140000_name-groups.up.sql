@@ -0,0 +1,18 @@
+CREATE TABLE name_groups
+(
+	id INTEGER NOT NULL PRIMARY KEY
+);
+
+CREATE TABLE name_group_members
+(
+	name     TEXT    NOT NULL PRIMARY KEY
+		REFERENCES names (name)
+			ON DELETE CASCADE,
+	group_id INTEGER NOT NULL
+		REFERENCES name_groups (id)
+			ON DELETE CASCADE,
+	-- manually merged or split names are left alone when regrouping automatically
+	manual   BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX name_group_members_by_group ON name_group_members (group_id);
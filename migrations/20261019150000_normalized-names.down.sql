@@ -0,0 +1,3 @@
+DROP TABLE pending_data_migrations;
+
+ALTER TABLE names DROP COLUMN folded_name;
@@ -0,0 +1,12 @@
+-- lowercase name without diacritics for case and diacritic insensitive matching, e.g. "zoe" for "Zoë"
+ALTER TABLE names ADD COLUMN folded_name TEXT NOT NULL DEFAULT '';
+
+-- Unicode normalization isn't available in SQLite, so existing names are normalized and merged by the application
+-- right after migrating, see `database::run_data_migrations`.
+CREATE TABLE pending_data_migrations
+(
+	name TEXT NOT NULL PRIMARY KEY
+);
+
+INSERT INTO pending_data_migrations (name)
+VALUES ('normalize_names');
@@ -0,0 +1,5 @@
+CREATE TABLE settings
+(
+	key   TEXT NOT NULL PRIMARY KEY,
+	value TEXT NOT NULL
+);
@@ -0,0 +1,5 @@
+-- user defined additions to the bundled blocklist of initials
+CREATE TABLE blocked_initials
+(
+	initials TEXT NOT NULL PRIMARY KEY
+);
@@ -0,0 +1 @@
+DROP TABLE combination_preference;
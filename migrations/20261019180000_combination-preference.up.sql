@@ -0,0 +1,15 @@
+-- ratings of first + middle name combinations, separate from the ratings of the single names
+CREATE TABLE combination_preference
+(
+	first_name  TEXT    NOT NULL
+		REFERENCES names (name)
+			ON DELETE CASCADE,
+	middle_name TEXT    NOT NULL
+		REFERENCES names (name)
+			ON DELETE CASCADE,
+	hyphenated  BOOLEAN NOT NULL,
+	preference  TEXT    NOT NULL
+		REFERENCES name_preference_values (value)
+			ON UPDATE CASCADE ON DELETE RESTRICT,
+	PRIMARY KEY (first_name, middle_name, hyphenated)
+);
@@ -0,0 +1,5 @@
+-- names of older siblings, these don't have to be part of the datasets
+CREATE TABLE siblings
+(
+	name TEXT NOT NULL PRIMARY KEY
+);
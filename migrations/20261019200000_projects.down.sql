@@ -0,0 +1,78 @@
+-- only the default project is kept
+CREATE TABLE global_siblings
+(
+	name TEXT NOT NULL PRIMARY KEY
+);
+
+INSERT INTO global_siblings (name)
+SELECT name
+FROM siblings
+WHERE project_id = 1;
+
+DROP TABLE siblings;
+
+ALTER TABLE global_siblings
+	RENAME TO siblings;
+
+CREATE TABLE global_combination_preference
+(
+	first_name  TEXT    NOT NULL
+		REFERENCES names (name)
+			ON DELETE CASCADE,
+	middle_name TEXT    NOT NULL
+		REFERENCES names (name)
+			ON DELETE CASCADE,
+	hyphenated  BOOLEAN NOT NULL,
+	preference  TEXT    NOT NULL
+		REFERENCES name_preference_values (value)
+			ON UPDATE CASCADE ON DELETE RESTRICT,
+	PRIMARY KEY (first_name, middle_name, hyphenated)
+);
+
+INSERT INTO global_combination_preference (first_name, middle_name, hyphenated, preference)
+SELECT first_name, middle_name, hyphenated, preference
+FROM combination_preference
+WHERE project_id = 1;
+
+DROP TABLE combination_preference;
+
+ALTER TABLE global_combination_preference
+	RENAME TO combination_preference;
+
+CREATE TABLE global_name_preference
+(
+	name       TEXT NOT NULL PRIMARY KEY
+		REFERENCES names (name)
+			ON DELETE CASCADE,
+	preference TEXT NOT NULL
+		REFERENCES name_preference_values (value)
+			ON UPDATE CASCADE ON DELETE RESTRICT
+);
+
+INSERT INTO global_name_preference (name, preference)
+SELECT name, preference
+FROM name_preference
+WHERE project_id = 1;
+
+DROP TABLE name_preference;
+
+ALTER TABLE global_name_preference
+	RENAME TO name_preference;
+
+CREATE TABLE settings
+(
+	key   TEXT NOT NULL PRIMARY KEY,
+	value TEXT NOT NULL
+);
+
+INSERT INTO settings (key, value)
+SELECT 'surname', surname
+FROM projects
+WHERE id = 1 AND surname IS NOT NULL;
+
+INSERT INTO settings (key, value)
+SELECT 'middle_name', middle_name
+FROM projects
+WHERE id = 1 AND middle_name IS NOT NULL;
+
+DROP TABLE projects;
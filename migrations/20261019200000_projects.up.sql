@@ -0,0 +1,91 @@
+-- one database can be used for several babies, each with their own preferences and family details,
+-- while names and name_records stay shared
+CREATE TABLE projects
+(
+	id          INTEGER NOT NULL PRIMARY KEY,
+	name        TEXT    NOT NULL UNIQUE,
+	surname     TEXT,
+	middle_name TEXT,
+	-- 'both' doesn't restrict the names
+	gender      TEXT    NOT NULL DEFAULT 'both',
+	due_date    TEXT
+);
+
+INSERT INTO projects (id, name, surname, middle_name)
+VALUES (
+	1,
+	'default',
+	(SELECT value FROM settings WHERE key = 'surname'),
+	(SELECT value FROM settings WHERE key = 'middle_name')
+);
+
+-- surname and middle name were the only settings
+DROP TABLE settings;
+
+CREATE TABLE project_name_preference
+(
+	project_id INTEGER NOT NULL
+		REFERENCES projects (id)
+			ON DELETE CASCADE,
+	name       TEXT    NOT NULL
+		REFERENCES names (name)
+			ON DELETE CASCADE,
+	preference TEXT    NOT NULL
+		REFERENCES name_preference_values (value)
+			ON UPDATE CASCADE ON DELETE RESTRICT,
+	PRIMARY KEY (project_id, name)
+);
+
+INSERT INTO project_name_preference (project_id, name, preference)
+SELECT 1, name, preference
+FROM name_preference;
+
+DROP TABLE name_preference;
+
+ALTER TABLE project_name_preference
+	RENAME TO name_preference;
+
+CREATE TABLE project_combination_preference
+(
+	project_id  INTEGER NOT NULL
+		REFERENCES projects (id)
+			ON DELETE CASCADE,
+	first_name  TEXT    NOT NULL
+		REFERENCES names (name)
+			ON DELETE CASCADE,
+	middle_name TEXT    NOT NULL
+		REFERENCES names (name)
+			ON DELETE CASCADE,
+	hyphenated  BOOLEAN NOT NULL,
+	preference  TEXT    NOT NULL
+		REFERENCES name_preference_values (value)
+			ON UPDATE CASCADE ON DELETE RESTRICT,
+	PRIMARY KEY (project_id, first_name, middle_name, hyphenated)
+);
+
+INSERT INTO project_combination_preference (project_id, first_name, middle_name, hyphenated, preference)
+SELECT 1, first_name, middle_name, hyphenated, preference
+FROM combination_preference;
+
+DROP TABLE combination_preference;
+
+ALTER TABLE project_combination_preference
+	RENAME TO combination_preference;
+
+CREATE TABLE project_siblings
+(
+	project_id INTEGER NOT NULL
+		REFERENCES projects (id)
+			ON DELETE CASCADE,
+	name       TEXT    NOT NULL,
+	PRIMARY KEY (project_id, name)
+);
+
+INSERT INTO project_siblings (project_id, name)
+SELECT 1, name
+FROM siblings;
+
+DROP TABLE siblings;
+
+ALTER TABLE project_siblings
+	RENAME TO siblings;
@@ -0,0 +1,2 @@
+DROP TABLE name_days;
+DROP TABLE name_metadata;
@@ -0,0 +1,25 @@
+CREATE TABLE name_metadata
+(
+	name    TEXT NOT NULL PRIMARY KEY
+		REFERENCES names (name)
+			ON DELETE CASCADE,
+	meaning        TEXT,
+	-- language of origin, e.g. "Greek"
+	origin         TEXT,
+	-- case and diacritic insensitive keys for searching, see `fold_name`
+	folded_meaning TEXT,
+	folded_origin  TEXT
+);
+
+CREATE TABLE name_days
+(
+	name     TEXT    NOT NULL
+		REFERENCES names (name)
+			ON DELETE CASCADE,
+	calendar TEXT    NOT NULL CHECK (calendar IN ('catholic', 'protestant')),
+	month    INTEGER NOT NULL CHECK (month BETWEEN 1 AND 12),
+	day      INTEGER NOT NULL CHECK (day BETWEEN 1 AND 31),
+	PRIMARY KEY (name, calendar, month, day)
+);
+
+CREATE INDEX name_days_by_month ON name_days (month);
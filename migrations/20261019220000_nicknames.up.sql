@@ -0,0 +1,10 @@
+-- imported nicknames, the ones following regular patterns are derived on the fly
+CREATE TABLE nicknames
+(
+	name     TEXT NOT NULL
+		REFERENCES names (name)
+			ON DELETE CASCADE,
+	-- doesn't have to be part of the datasets
+	nickname TEXT NOT NULL,
+	PRIMARY KEY (name, nickname)
+);
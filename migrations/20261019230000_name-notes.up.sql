@@ -0,0 +1,16 @@
+CREATE TABLE name_notes
+(
+	id         INTEGER NOT NULL PRIMARY KEY,
+	project_id INTEGER NOT NULL
+		REFERENCES projects (id)
+			ON DELETE CASCADE,
+	name       TEXT    NOT NULL
+		REFERENCES names (name)
+			ON DELETE CASCADE,
+	-- who wrote the note, optional
+	voter      TEXT,
+	note       TEXT    NOT NULL,
+	created_at TEXT    NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX name_notes_by_name ON name_notes (project_id, name);
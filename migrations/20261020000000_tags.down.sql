@@ -0,0 +1,2 @@
+DROP TABLE name_tags;
+DROP TABLE tags;
@@ -0,0 +1,22 @@
+CREATE TABLE tags
+(
+	id         INTEGER NOT NULL PRIMARY KEY,
+	project_id INTEGER NOT NULL
+		REFERENCES projects (id)
+			ON DELETE CASCADE,
+	name       TEXT    NOT NULL COLLATE NOCASE,
+	UNIQUE (project_id, name)
+);
+
+CREATE TABLE name_tags
+(
+	tag_id INTEGER NOT NULL
+		REFERENCES tags (id)
+			ON DELETE CASCADE,
+	name   TEXT    NOT NULL
+		REFERENCES names (name)
+			ON DELETE CASCADE,
+	PRIMARY KEY (tag_id, name)
+);
+
+CREATE INDEX name_tags_by_name ON name_tags (name);
@@ -0,0 +1,2 @@
+ALTER TABLE name_preference
+	DROP COLUMN stars;
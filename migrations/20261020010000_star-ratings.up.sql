@@ -0,0 +1,7 @@
+-- optional rating of 1 to 5 stars, kept when a name is vetoed so it comes back when the veto is lifted
+ALTER TABLE name_preference
+	ADD COLUMN stars INTEGER CHECK (stars BETWEEN 1 AND 5);
+
+UPDATE name_preference
+SET stars = 5
+WHERE preference = 'favorite';
@@ -0,0 +1 @@
+DROP TABLE preference_history;
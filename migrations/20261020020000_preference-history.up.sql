@@ -0,0 +1,27 @@
+-- every change of a name preference, to see how the opinion on a name evolved and to restore earlier states
+CREATE TABLE preference_history
+(
+	id             INTEGER NOT NULL PRIMARY KEY,
+	project_id     INTEGER NOT NULL
+		REFERENCES projects (id)
+			ON DELETE CASCADE,
+	-- no reference to names, so the history outlives merged spellings
+	name           TEXT    NOT NULL,
+	-- NULL means undecided
+	old_preference TEXT,
+	old_stars      INTEGER,
+	new_preference TEXT,
+	new_stars      INTEGER,
+	-- who changed the preference, optional
+	voter          TEXT,
+	changed_at     TEXT    NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX preference_history_by_name ON preference_history (project_id, name);
+
+CREATE TRIGGER preference_history_append_only
+	BEFORE UPDATE
+	ON preference_history
+BEGIN
+	SELECT RAISE(ABORT, 'The preference history is append only');
+END;
@@ -0,0 +1,4 @@
+ALTER TABLE name_preference
+	DROP COLUMN review_interval_days;
+ALTER TABLE name_preference
+	DROP COLUMN confirmed_at;
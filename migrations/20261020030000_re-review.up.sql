@@ -0,0 +1,9 @@
+-- spaced re-review of favorites: a favorite is due again once the interval has passed since it was last confirmed,
+-- the interval grows whenever it is reconfirmed and is reset when hesitating
+ALTER TABLE name_preference
+	ADD COLUMN confirmed_at TEXT;
+ALTER TABLE name_preference
+	ADD COLUMN review_interval_days INTEGER NOT NULL DEFAULT 3;
+
+UPDATE name_preference
+SET confirmed_at = CURRENT_TIMESTAMP;
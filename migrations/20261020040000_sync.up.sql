@@ -0,0 +1,45 @@
+CREATE TABLE sync_state
+(
+	-- only a single row
+	id                 INTEGER NOT NULL PRIMARY KEY CHECK (id = 1),
+	-- identifies this database in the operations that are exchanged with other copies of it by `sync`
+	device_id          TEXT    NOT NULL,
+	-- the last operation written by `sync export`
+	exported_operation INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT INTO sync_state (id, device_id)
+VALUES (1, lower(hex(randomblob(8))));
+
+-- every change of a name preference, made in this database or imported from another one
+CREATE TABLE sync_operations
+(
+	-- local order, `sync export` continues after the last exported operation
+	id         INTEGER NOT NULL PRIMARY KEY,
+	device_id  TEXT    NOT NULL,
+	-- Lamport clock, larger than the clocks of all operations known to the device when the change was made
+	clock      INTEGER NOT NULL,
+	-- project ids differ between databases, their names don't
+	project    TEXT    NOT NULL,
+	-- no reference to names, so operations on names that are missing in this database are still passed on
+	name       TEXT    NOT NULL,
+	-- NULL means undecided
+	preference TEXT,
+	stars      INTEGER,
+	voter      TEXT,
+	UNIQUE (device_id, clock)
+);
+
+CREATE INDEX sync_operations_by_name ON sync_operations (project, name, clock);
+
+-- existing preferences are synced as if they were just made
+INSERT INTO sync_operations (device_id, clock, project, name, preference, stars)
+SELECT
+	(SELECT device_id FROM sync_state),
+	row_number() OVER (ORDER BY projects.name, name_preference.name),
+	projects.name,
+	name_preference.name,
+	name_preference.preference,
+	name_preference.stars
+FROM name_preference
+	INNER JOIN projects ON projects.id = name_preference.project_id;
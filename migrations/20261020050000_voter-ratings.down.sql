@@ -0,0 +1 @@
+DROP TABLE voter_ratings;
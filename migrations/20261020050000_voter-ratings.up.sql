@@ -0,0 +1,14 @@
+-- ratings of single voters, kept next to the shared rating of the project when merging with `separate-voters`
+CREATE TABLE voter_ratings
+(
+	project_id INTEGER NOT NULL
+		REFERENCES projects (id)
+			ON DELETE CASCADE,
+	name       TEXT    NOT NULL
+		REFERENCES names (name)
+			ON DELETE CASCADE,
+	voter      TEXT    NOT NULL,
+	preference TEXT    NOT NULL,
+	stars      INTEGER,
+	PRIMARY KEY (project_id, name, voter)
+);
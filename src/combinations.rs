@@ -0,0 +1,116 @@
+//! Combinations of a first and a middle name, either as two given names ("Anna Lena") or as a hyphenated double
+//! name ("Anna-Lena").
+
+use crate::name_normalization::fold_name;
+use crate::name_structure::{Language, letter_count, syllable_count};
+use crate::surname::{MAXIMUM_COMBINED_LETTERS, alliteration, rhymes, vowel_clash};
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Combination {
+	pub first_name: String,
+	pub middle_name: String,
+	pub hyphenated: bool,
+}
+
+impl Display for Combination {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+		let separator = if self.hyphenated { '-' } else { ' ' };
+		write!(formatter, "{}{separator}{}", self.first_name, self.middle_name)
+	}
+}
+
+/// How well a combination works, on its own and together with the surname if there is one
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[expect(clippy::struct_excessive_bools)]
+pub struct CombinationAnalysis {
+	/// First and middle name start with the same letter, like "Lena Luisa"
+	pub alliteration: bool,
+	/// First and middle name rhyme, like "Jana Hanna"
+	pub rhyme: bool,
+	/// One of the names ends and the next one starts with a vowel, like "Anna Emilia"
+	pub vowel_clash: bool,
+	/// The middle name rhymes with the surname
+	pub rhyme_with_surname: bool,
+	/// Syllables of first name, middle name and surname
+	pub syllables: Vec<usize>,
+	/// Letters of first name, middle name and surname
+	pub combined_letters: usize,
+}
+
+impl CombinationAnalysis {
+	pub fn new(combination: &Combination, surname: Option<&str>, language: Language) -> Self {
+		let names = [
+			Some(combination.first_name.as_str()),
+			Some(combination.middle_name.as_str()),
+			surname,
+		]
+		.into_iter()
+		.flatten()
+		.collect::<Vec<_>>();
+		let folded_names = names.iter().map(|name| fold_name(name)).collect::<Vec<_>>();
+		let [folded_first_name, folded_middle_name, ..] = folded_names.as_slice() else {
+			unreachable!("First and middle name are always there");
+		};
+
+		Self {
+			alliteration: alliteration(folded_first_name, folded_middle_name),
+			rhyme: rhymes(folded_first_name, folded_middle_name),
+			vowel_clash: folded_names.windows(2).any(|pair| vowel_clash(&pair[0], &pair[1])),
+			rhyme_with_surname: folded_names
+				.get(2)
+				.is_some_and(|folded_surname| rhymes(folded_middle_name, folded_surname)),
+			syllables: names.iter().map(|name| syllable_count(name, language)).collect(),
+			combined_letters: names.iter().map(|name| letter_count(name)).sum(),
+		}
+	}
+
+	/// All names having the same number of syllables sounds monotonous, like "Anna Lena Weber"
+	pub fn monotonous_rhythm(&self) -> bool {
+		self.syllables.windows(2).all(|pair| pair[0] == pair[1])
+	}
+
+	pub fn too_long(&self) -> bool {
+		self.combined_letters > MAXIMUM_COMBINED_LETTERS
+	}
+
+	/// From 0 (lots of issues) to 100 (no issues at all)
+	pub fn score(&self) -> u8 {
+		let penalties = [
+			(self.alliteration, 10),
+			(self.rhyme, 30),
+			(self.vowel_clash, 15),
+			(self.rhyme_with_surname, 20),
+			(self.monotonous_rhythm(), 15),
+			(self.too_long(), 15),
+		];
+		penalties
+			.into_iter()
+			.filter(|(applies, _)| *applies)
+			.fold(100u8, |score, (_, penalty)| score.saturating_sub(penalty))
+	}
+
+	/// Human readable description of everything that doesn't fit well
+	pub fn issues(&self) -> Vec<String> {
+		let mut issues = Vec::new();
+		if self.alliteration {
+			issues.push("Alliteration: both given names start with the same letter".to_owned());
+		}
+		if self.rhyme {
+			issues.push("Rhyme: both given names end the same way".to_owned());
+		}
+		if self.vowel_clash {
+			issues.push("Vowel clash: the names run into each other".to_owned());
+		}
+		if self.rhyme_with_surname {
+			issues.push("Rhyme: the middle name ends like the surname".to_owned());
+		}
+		if self.monotonous_rhythm() {
+			issues.push("Monotonous rhythm: all names have the same number of syllables".to_owned());
+		}
+		if self.too_long() {
+			issues.push(format!("Long: {} letters combined", self.combined_letters));
+		}
+		issues
+	}
+}
@@ -1,10 +1,17 @@
+use crate::name_normalization::normalize_name;
 use serde::Deserialize;
 use std::path::Path;
 
 pub fn parse_csv(path: &Path) -> anyhow::Result<impl Iterator<Item = anyhow::Result<NameRecord>> + use<>> {
 	// FIXME: Can I turn this ? into an  error result as part of the iterator itself?
 	let reader = csv::Reader::from_path(path)?;
-	Ok(reader.into_deserialize().map(|result| result.map_err(Into::into)))
+	Ok(reader.into_deserialize().map(|result| {
+		let record: NameRecord = result?;
+		Ok(NameRecord {
+			name: normalize_name(&record.name),
+			..record
+		})
+	}))
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -15,7 +22,18 @@ pub struct NameRecord {
 }
 
 #[derive(
-	Clone, Copy, Debug, Deserialize, sqlx::Type, strum::Display, strum::EnumString, strum::AsRefStr, strum::VariantNames,
+	Clone,
+	Copy,
+	Debug,
+	PartialEq,
+	Eq,
+	Hash,
+	Deserialize,
+	sqlx::Type,
+	strum::Display,
+	strum::EnumString,
+	strum::AsRefStr,
+	strum::VariantNames,
 )]
 #[serde(rename_all = "snake_case")]
 #[sqlx(rename_all = "snake_case")]
@@ -1,17 +1,208 @@
+use crate::combinations::Combination;
 use crate::csv_parser::{Gender, NameRecord};
+use crate::initials::InitialsBlocklist;
+use crate::name_metadata::{NameDay, NameMetadataRecord};
+use crate::name_normalization::{fold_name, normalize_name};
+use crate::nicknames::NicknameRecord;
+use crate::phonetics::{PhoneticCode, phonetic_codes};
+use crate::sync::SyncOperation;
+use chrono::{NaiveDate, NaiveDateTime};
 use futures_util::{Stream, TryStreamExt, stream};
-use sqlx::SqlitePool;
 use sqlx::sqlite::{SqliteAutoVacuum, SqliteConnectOptions, SqliteJournalMode, SqliteSynchronous};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use std::fmt::{Display, Formatter};
 use std::path::Path;
 
 pub async fn initialize(path: &Path) -> anyhow::Result<SqlitePool> {
 	let pool = SqlitePool::connect_with(connect_options(path)).await?;
 
 	sqlx::migrate!("./migrations").run(&pool).await?;
+	run_data_migrations(&pool).await?;
 
 	Ok(pool)
 }
 
+/// Migrations that can't be expressed in SQL, they are queued by SQL migrations via `pending_data_migrations`.
+async fn run_data_migrations(database_pool: &SqlitePool) -> anyhow::Result<()> {
+	let pending_migrations = sqlx::query_scalar!(
+		r#"
+		SELECT name
+		FROM pending_data_migrations
+		ORDER BY name ASC
+		"#,
+	)
+	.fetch_all(database_pool)
+	.await?;
+
+	for migration in pending_migrations {
+		match migration.as_str() {
+			"normalize_names" => normalize_names(database_pool).await?,
+			unknown => anyhow::bail!("Unknown data migration '{unknown}'"),
+		}
+
+		sqlx::query!(
+			r#"
+			DELETE FROM pending_data_migrations
+			WHERE name = $1
+			"#,
+			migration,
+		)
+		.execute(database_pool)
+		.await?;
+	}
+
+	Ok(())
+}
+
+/// Normalizes the spelling of all names (see [`normalize_name`]) and merges the ones that turn out to be duplicates.
+async fn normalize_names(database_pool: &SqlitePool) -> anyhow::Result<()> {
+	let names = list_all(Gender::Both, database_pool).try_collect::<Vec<_>>().await?;
+	for Name { name, .. } in names {
+		let normalized_name = normalize_name(&name);
+		if normalized_name != name {
+			merge_name_into(&name, &normalized_name, database_pool).await?;
+			replace_phonetic_codes(&normalized_name, &phonetic_codes(&normalized_name), database_pool).await?;
+		}
+
+		let folded_name = fold_name(&normalized_name);
+		sqlx::query!(
+			r#"
+			UPDATE names
+			SET folded_name = $2
+			WHERE name = $1
+			"#,
+			normalized_name,
+			folded_name,
+		)
+		.execute(database_pool)
+		.await?;
+	}
+	Ok(())
+}
+
+/// Moves everything that is known about a name over to another spelling of it and removes the original one.
+async fn merge_name_into(name: &str, target: &str, database_pool: &SqlitePool) -> sqlx::Result<()> {
+	let mut transaction = database_pool.begin().await?;
+	sqlx::query!(
+		r#"
+		INSERT INTO names (
+			name,
+			gender
+		)
+		SELECT $2, gender
+		FROM names
+		WHERE name = $1
+		ON CONFLICT DO UPDATE
+		SET
+			gender = CASE
+				WHEN names.gender = excluded.gender THEN names.gender
+				ELSE 'both'
+			END
+		"#,
+		name,
+		target,
+	)
+	.execute(&mut *transaction)
+	.await?;
+
+	merge_name_records(name, target, &mut transaction).await?;
+
+	// if the spellings were rated differently, a no-go wins: looking at a name again is better than favoring a veto
+	sqlx::query!(
+		r#"
+		INSERT INTO name_preference (
+			project_id,
+			name,
+			preference,
+			stars,
+			confirmed_at,
+			review_interval_days
+		)
+		SELECT project_id, $2, preference, stars, confirmed_at, review_interval_days
+		FROM name_preference
+		WHERE name = $1
+		ON CONFLICT DO UPDATE
+		SET
+			preference = CASE excluded.preference
+				WHEN 'no_go' THEN 'no_go'
+				ELSE name_preference.preference
+			END,
+			stars = coalesce(max(excluded.stars, name_preference.stars), excluded.stars, name_preference.stars)
+		"#,
+		name,
+		target,
+	)
+	.execute(&mut *transaction)
+	.await?;
+
+	sqlx::query!(
+		r#"
+		INSERT INTO name_group_members (
+			name,
+			group_id,
+			manual
+		)
+		SELECT $2, group_id, manual
+		FROM name_group_members
+		WHERE name = $1
+		ON CONFLICT DO NOTHING
+		"#,
+		name,
+		target,
+	)
+	.execute(&mut *transaction)
+	.await?;
+
+	// preferences, phonetic codes and group memberships of the original spelling are removed by cascading
+	sqlx::query!(
+		r#"
+		DELETE FROM names
+		WHERE name = $1
+		"#,
+		name,
+	)
+	.execute(&mut *transaction)
+	.await?;
+
+	delete_empty_name_groups(&mut transaction).await?;
+	transaction.commit().await
+}
+
+async fn merge_name_records(name: &str, target: &str, transaction: &mut Transaction<'_, Sqlite>) -> sqlx::Result<()> {
+	// the same dataset can contain several spellings, e.g. "Zoë" both in NFC and NFD
+	sqlx::query!(
+		r#"
+		INSERT INTO name_records (
+			name,
+			count,
+			gender,
+			source
+		)
+		SELECT $2, count, gender, source
+		FROM name_records
+		WHERE name = $1
+		ON CONFLICT DO UPDATE
+		SET
+			count = name_records.count + excluded.count
+		"#,
+		name,
+		target,
+	)
+	.execute(&mut **transaction)
+	.await?;
+
+	sqlx::query!(
+		r#"
+		DELETE FROM name_records
+		WHERE name = $1
+		"#,
+		name,
+	)
+	.execute(&mut **transaction)
+	.await?;
+	Ok(())
+}
+
 fn connect_options(path: &Path) -> SqliteConnectOptions {
 	SqliteConnectOptions::new()
 		.filename(path)
@@ -23,12 +214,14 @@ fn connect_options(path: &Path) -> SqliteConnectOptions {
 }
 
 pub async fn upsert_name(name: &str, gender: Gender, database_pool: &SqlitePool) -> anyhow::Result<()> {
+	let folded_name = fold_name(name);
 	sqlx::query!(
 		r#"
 		INSERT INTO names (
 			name,
-			gender
-		) VALUES ($1, $2)
+			gender,
+			folded_name
+		) VALUES ($1, $2, $3)
 		ON CONFLICT DO
 		UPDATE
 		SET
@@ -48,6 +241,7 @@ pub async fn upsert_name(name: &str, gender: Gender, database_pool: &SqlitePool)
 		"#,
 		name,
 		gender,
+		folded_name,
 	)
 	.execute(database_pool)
 	.await?;
@@ -79,20 +273,456 @@ pub async fn insert_name_record(
 	Ok(())
 }
 
+pub async fn replace_phonetic_codes(
+	name: &str,
+	codes: &[PhoneticCode],
+	database_pool: &SqlitePool,
+) -> sqlx::Result<()> {
+	let mut transaction = database_pool.begin().await?;
+	sqlx::query!(
+		r#"
+		DELETE FROM phonetic_codes
+		WHERE name = $1
+		"#,
+		name,
+	)
+	.execute(&mut *transaction)
+	.await?;
+
+	for PhoneticCode { algorithm, code } in codes {
+		sqlx::query!(
+			r#"
+			INSERT INTO phonetic_codes (
+				name,
+				algorithm,
+				code
+			) VALUES ($1, $2, $3)
+			ON CONFLICT DO NOTHING
+			"#,
+			name,
+			algorithm,
+			code,
+		)
+		.execute(&mut *transaction)
+		.await?;
+	}
+
+	transaction.commit().await
+}
+
+/// Replaces all metadata of the name, returns `false` without changing anything if the name isn't in the database
+pub async fn replace_name_metadata(
+	NameMetadataRecord {
+		name,
+		meaning,
+		origin,
+		name_days,
+	}: &NameMetadataRecord,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<bool> {
+	let folded_meaning = meaning.as_deref().map(fold_name);
+	let folded_origin = origin.as_deref().map(fold_name);
+
+	let mut transaction = database_pool.begin().await?;
+	let inserted = sqlx::query!(
+		r#"
+		INSERT INTO name_metadata (
+			name,
+			meaning,
+			origin,
+			folded_meaning,
+			folded_origin
+		)
+		SELECT $1, $2, $3, $4, $5
+		WHERE EXISTS (SELECT 1 FROM names WHERE name = $1)
+		ON CONFLICT DO UPDATE
+		SET
+			meaning = $2,
+			origin = $3,
+			folded_meaning = $4,
+			folded_origin = $5
+		"#,
+		name,
+		meaning,
+		origin,
+		folded_meaning,
+		folded_origin,
+	)
+	.execute(&mut *transaction)
+	.await?
+	.rows_affected();
+	if inserted == 0 {
+		return Ok(false);
+	}
+
+	sqlx::query!(
+		r#"
+		DELETE FROM name_days
+		WHERE name = $1
+		"#,
+		name,
+	)
+	.execute(&mut *transaction)
+	.await?;
+
+	for NameDay { calendar, month, day } in name_days {
+		sqlx::query!(
+			r#"
+			INSERT INTO name_days (
+				name,
+				calendar,
+				month,
+				day
+			) VALUES ($1, $2, $3, $4)
+			ON CONFLICT DO NOTHING
+			"#,
+			name,
+			calendar,
+			month,
+			day,
+		)
+		.execute(&mut *transaction)
+		.await?;
+	}
+
+	transaction.commit().await?;
+	Ok(true)
+}
+
+/// Returns `false` without inserting anything if the name isn't in the database
+pub async fn insert_nickname(
+	NicknameRecord { name, nickname }: &NicknameRecord,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<bool> {
+	let inserted = sqlx::query!(
+		r#"
+		INSERT INTO nicknames (
+			name,
+			nickname
+		)
+		SELECT $1, $2
+		WHERE EXISTS (SELECT 1 FROM names WHERE name = $1)
+		ON CONFLICT DO NOTHING
+		"#,
+		name,
+		nickname,
+	)
+	.execute(database_pool)
+	.await?
+	.rows_affected();
+	if inserted > 0 {
+		return Ok(true);
+	}
+
+	// already imported before
+	name_exists(name, database_pool).await
+}
+
+pub async fn insert_name_note(
+	name: &str,
+	voter: Option<&str>,
+	note: &str,
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<i64> {
+	sqlx::query_scalar!(
+		r#"
+		INSERT INTO name_notes (
+			project_id,
+			name,
+			voter,
+			note
+		) VALUES ($1, $2, $3, $4)
+		RETURNING id
+		"#,
+		project_id,
+		name,
+		voter,
+		note,
+	)
+	.fetch_one(database_pool)
+	.await
+}
+
+pub async fn read_names_with_notes(project_id: ProjectId, database_pool: &SqlitePool) -> sqlx::Result<Vec<String>> {
+	sqlx::query_scalar!(
+		r#"
+		SELECT DISTINCT name
+		FROM name_notes
+		WHERE project_id = $1
+		ORDER BY name ASC
+		"#,
+		project_id,
+	)
+	.fetch_all(database_pool)
+	.await
+}
+
+/// Keeps the time the note was originally written, if known. Returns `false` if the name already has the same note by
+/// the same voter, so importing a file twice doesn't duplicate notes.
+async fn insert_imported_note(
+	ImportedNote {
+		name,
+		voter,
+		note,
+		created_at,
+	}: &ImportedNote,
+	project_id: ProjectId,
+	transaction: &mut Transaction<'_, Sqlite>,
+) -> sqlx::Result<bool> {
+	let inserted = sqlx::query!(
+		r#"
+		INSERT INTO name_notes (
+			project_id,
+			name,
+			voter,
+			note,
+			created_at
+		)
+		SELECT $1, $2, $3, $4, coalesce($5, CURRENT_TIMESTAMP)
+		WHERE NOT EXISTS (
+			SELECT 1
+			FROM name_notes
+			WHERE
+				project_id = $1
+				AND name = $2
+				AND voter IS $3
+				AND note = $4
+		)
+		"#,
+		project_id,
+		name,
+		voter,
+		note,
+		created_at,
+	)
+	.execute(&mut **transaction)
+	.await?
+	.rows_affected();
+	Ok(inserted > 0)
+}
+
+pub async fn name_exists(name: &str, database_pool: &SqlitePool) -> sqlx::Result<bool> {
+	sqlx::query_scalar!(
+		r#"
+		SELECT EXISTS (SELECT 1 FROM names WHERE name = $1) as "exists!: bool"
+		"#,
+		name,
+	)
+	.fetch_one(database_pool)
+	.await
+}
+
+/// Returns `false` if there was no such note in the project
+pub async fn delete_name_note(id: i64, project_id: ProjectId, database_pool: &SqlitePool) -> sqlx::Result<bool> {
+	let deleted = sqlx::query!(
+		r#"
+		DELETE FROM name_notes
+		WHERE
+			id = $1
+			AND project_id = $2
+		"#,
+		id,
+		project_id,
+	)
+	.execute(database_pool)
+	.await?
+	.rows_affected();
+	Ok(deleted > 0)
+}
+
+/// All tags of the project, alphabetically
+pub async fn read_tags(project_id: ProjectId, database_pool: &SqlitePool) -> sqlx::Result<Vec<String>> {
+	sqlx::query_scalar!(
+		r#"
+		SELECT name
+		FROM tags
+		WHERE project_id = $1
+		ORDER BY name ASC
+		"#,
+		project_id,
+	)
+	.fetch_all(database_pool)
+	.await
+}
+
+/// Creates the tag if it doesn't exist yet, returns how many names were newly tagged
+pub async fn tag_names(
+	tag: &str,
+	names: &[String],
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<u64> {
+	let mut transaction = database_pool.begin().await?;
+	sqlx::query!(
+		r#"
+		INSERT INTO tags (
+			project_id,
+			name
+		) VALUES ($1, $2)
+		ON CONFLICT DO NOTHING
+		"#,
+		project_id,
+		tag,
+	)
+	.execute(&mut *transaction)
+	.await?;
+
+	let mut tagged = 0;
+	for name in names {
+		tagged += sqlx::query!(
+			r#"
+			INSERT INTO name_tags (
+				tag_id,
+				name
+			)
+			SELECT id, $3
+			FROM tags
+			WHERE
+				project_id = $1
+				AND name = $2
+				AND EXISTS (SELECT 1 FROM names WHERE name = $3)
+			ON CONFLICT DO NOTHING
+			"#,
+			project_id,
+			tag,
+			name,
+		)
+		.execute(&mut *transaction)
+		.await?
+		.rows_affected();
+	}
+
+	transaction.commit().await?;
+	Ok(tagged)
+}
+
+/// Returns how many names had the tag
+pub async fn untag_names(
+	tag: &str,
+	names: &[String],
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<u64> {
+	let mut transaction = database_pool.begin().await?;
+	let mut untagged = 0;
+	for name in names {
+		untagged += sqlx::query!(
+			r#"
+			DELETE FROM name_tags
+			WHERE
+				name = $3
+				AND tag_id = (SELECT id FROM tags WHERE project_id = $1 AND name = $2)
+			"#,
+			project_id,
+			tag,
+			name,
+		)
+		.execute(&mut *transaction)
+		.await?
+		.rows_affected();
+	}
+
+	transaction.commit().await?;
+	Ok(untagged)
+}
+
+/// Deletes the tag and removes it from all names, returns `false` if there was no such tag
+pub async fn delete_tag(tag: &str, project_id: ProjectId, database_pool: &SqlitePool) -> sqlx::Result<bool> {
+	let deleted = sqlx::query!(
+		r#"
+		DELETE FROM tags
+		WHERE
+			project_id = $1
+			AND name = $2
+		"#,
+		project_id,
+		tag,
+	)
+	.execute(database_pool)
+	.await?
+	.rows_affected();
+	Ok(deleted > 0)
+}
+
 #[derive(Debug, Clone)]
 pub struct Name {
 	pub name: String,
 	pub gender: Gender,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, sqlx::Type, strum::AsRefStr)]
+#[derive(
+	Clone,
+	Copy,
+	Debug,
+	PartialEq,
+	Eq,
+	sqlx::Type,
+	strum::AsRefStr,
+	strum::EnumString,
+	serde::Serialize,
+	serde::Deserialize,
+)]
 #[sqlx(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum NamePreference {
 	Favorite,
 	NoGo,
 }
 
+pub const MAXIMUM_STARS: u8 = 5;
+
+/// Favorites come up for a re-review this many days after they were favored, see [`confirm_favorite`]
+pub const INITIAL_REVIEW_INTERVAL_DAYS: i64 = 3;
+/// Even favorites that were confirmed over and over come up again at least twice a year
+const MAXIMUM_REVIEW_INTERVAL_DAYS: i64 = 180;
+
+/// Preference of a name together with its rating from 1 to [`MAXIMUM_STARS`], favorites are rated and a vetoed name
+/// keeps its stars for when the veto is lifted again
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rating {
+	pub preference: Option<NamePreference>,
+	pub stars: Option<u8>,
+}
+
+impl Rating {
+	/// The rating after changing only the preference. A veto keeps the stars for when it is lifted again, favorites
+	/// without a rating yet get the full stars.
+	pub fn with_preference(self, preference: Option<NamePreference>) -> Self {
+		let stars = match preference {
+			Some(NamePreference::Favorite) => Some(self.stars.unwrap_or(MAXIMUM_STARS)),
+			Some(NamePreference::NoGo) => self.stars,
+			None => None,
+		};
+		Self { preference, stars }
+	}
+}
+
+impl Display for Rating {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Rating { preference: None, .. } => write!(formatter, "undecided"),
+			Rating {
+				preference: Some(NamePreference::NoGo),
+				..
+			} => write!(formatter, "no-go"),
+			Rating {
+				preference: Some(NamePreference::Favorite),
+				stars: Some(1),
+			} => write!(formatter, "1 star"),
+			Rating {
+				preference: Some(NamePreference::Favorite),
+				stars: Some(stars),
+			} => write!(formatter, "{stars} stars"),
+			Rating {
+				preference: Some(NamePreference::Favorite),
+				stars: None,
+			} => write!(formatter, "favorite"),
+		}
+	}
+}
+
 pub fn list_all(gender: Gender, database_pool: &SqlitePool) -> impl Stream<Item = sqlx::Result<Name>> + use<> {
 	const BULK_SIZE: i64 = 10;
 	let database_pool = database_pool.clone();
@@ -152,35 +782,1029 @@ pub async fn read_random(gender: Gender, database_pool: &SqlitePool) -> sqlx::Re
 	.await
 }
 
-pub async fn upsert_name_preference(
+async fn upsert_name_preference(
 	name: &str,
 	preference: NamePreference,
-	database_pool: &SqlitePool,
+	stars: Option<u8>,
+	project_id: ProjectId,
+	transaction: &mut Transaction<'_, Sqlite>,
 ) -> sqlx::Result<()> {
 	sqlx::query!(
 		r#"
 		INSERT INTO name_preference (
+			project_id,
 			name,
-			preference
-		) VALUES ($1, $2)
+			preference,
+			stars,
+			confirmed_at,
+			review_interval_days
+		) VALUES ($1, $2, $3, $4, CURRENT_TIMESTAMP, $5)
 		ON CONFLICT DO UPDATE
 		SET
-			preference = $2
+			-- a name that just became a favorite starts the re-review schedule from the beginning
+			confirmed_at = CASE name_preference.preference
+				WHEN $3 THEN name_preference.confirmed_at
+				ELSE CURRENT_TIMESTAMP
+			END,
+			review_interval_days = CASE name_preference.preference
+				WHEN $3 THEN name_preference.review_interval_days
+				ELSE $5
+			END,
+			preference = $3,
+			stars = $4
 		"#,
+		project_id,
 		name,
 		preference,
+		stars,
+		INITIAL_REVIEW_INTERVAL_DAYS,
 	)
-	.execute(database_pool)
+	.execute(&mut **transaction)
 	.await?;
 	Ok(())
 }
 
-pub async fn delete_name_preference(name: &str, database_pool: &SqlitePool) -> sqlx::Result<()> {
-	sqlx::query!(
+/// Confirms a favorite that was due for a re-review, it comes up again after twice the previous interval.
+/// Returns `false` if the name isn't a favorite.
+pub async fn confirm_favorite(name: &str, project_id: ProjectId, database_pool: &SqlitePool) -> sqlx::Result<bool> {
+	let result = sqlx::query!(
 		r#"
-		DELETE FROM name_preference
-		WHERE name = $1
+		UPDATE name_preference
+		SET
+			confirmed_at = CURRENT_TIMESTAMP,
+			review_interval_days = min(review_interval_days * 2, $3)
+		WHERE
+			project_id = $1
+			AND name = $2
+			AND preference = 'favorite'
+		"#,
+		project_id,
+		name,
+		MAXIMUM_REVIEW_INTERVAL_DAYS,
+	)
+	.execute(database_pool)
+	.await?;
+	Ok(result.rows_affected() > 0)
+}
+
+/// Hesitating about a favorite keeps it, but starts the re-review schedule from the beginning.
+/// Returns `false` if the name isn't a favorite.
+pub async fn hesitate_on_favorite(name: &str, project_id: ProjectId, database_pool: &SqlitePool) -> sqlx::Result<bool> {
+	let result = sqlx::query!(
+		r#"
+		UPDATE name_preference
+		SET
+			confirmed_at = CURRENT_TIMESTAMP,
+			review_interval_days = $3
+		WHERE
+			project_id = $1
+			AND name = $2
+			AND preference = 'favorite'
+		"#,
+		project_id,
+		name,
+		INITIAL_REVIEW_INTERVAL_DAYS,
+	)
+	.execute(database_pool)
+	.await?;
+	Ok(result.rows_affected() > 0)
+}
+
+async fn delete_name_preference(
+	name: &str,
+	project_id: ProjectId,
+	transaction: &mut Transaction<'_, Sqlite>,
+) -> sqlx::Result<()> {
+	sqlx::query!(
+		r#"
+		DELETE FROM name_preference
+		WHERE
+			project_id = $1
+			AND name = $2
+		"#,
+		project_id,
+		name,
+	)
+	.execute(&mut **transaction)
+	.await?;
+	Ok(())
+}
+
+async fn read_name_rating(
+	name: &str,
+	project_id: ProjectId,
+	transaction: &mut Transaction<'_, Sqlite>,
+) -> sqlx::Result<Rating> {
+	let rating = sqlx::query_as!(
+		Rating,
+		r#"
+		SELECT
+			preference as "preference?: NamePreference",
+			stars as "stars?: u8"
+		FROM name_preference
+		WHERE
+			project_id = $1
+			AND name = $2
+		"#,
+		project_id,
+		name,
+	)
+	.fetch_optional(&mut **transaction)
+	.await?;
+	Ok(rating.unwrap_or_default())
+}
+
+pub async fn upsert_combination_preference(
+	Combination {
+		first_name,
+		middle_name,
+		hyphenated,
+	}: &Combination,
+	preference: NamePreference,
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<()> {
+	sqlx::query!(
+		r#"
+		INSERT INTO combination_preference (
+			project_id,
+			first_name,
+			middle_name,
+			hyphenated,
+			preference
+		) VALUES ($1, $2, $3, $4, $5)
+		ON CONFLICT DO UPDATE
+		SET
+			preference = $5
+		"#,
+		project_id,
+		first_name,
+		middle_name,
+		hyphenated,
+		preference,
+	)
+	.execute(database_pool)
+	.await?;
+	Ok(())
+}
+
+pub async fn delete_combination_preference(
+	Combination {
+		first_name,
+		middle_name,
+		hyphenated,
+	}: &Combination,
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<()> {
+	sqlx::query!(
+		r#"
+		DELETE FROM combination_preference
+		WHERE
+			project_id = $1
+			AND first_name = $2
+			AND middle_name = $3
+			AND hyphenated = $4
+		"#,
+		project_id,
+		first_name,
+		middle_name,
+		hyphenated,
+	)
+	.execute(database_pool)
+	.await?;
+	Ok(())
+}
+
+/// Replaces all automatically determined groups of spelling variants, manually grouped names are kept as they are.
+pub async fn replace_automatic_name_groups(groups: &[Vec<String>], database_pool: &SqlitePool) -> sqlx::Result<()> {
+	let mut transaction = database_pool.begin().await?;
+	sqlx::query!(
+		r#"
+		DELETE FROM name_group_members
+		WHERE NOT manual
+		"#,
+	)
+	.execute(&mut *transaction)
+	.await?;
+
+	for group in groups {
+		let group_id = sqlx::query_scalar!(
+			r#"
+			INSERT INTO name_groups DEFAULT VALUES
+			RETURNING id
+			"#,
+		)
+		.fetch_one(&mut *transaction)
+		.await?;
+
+		for name in group {
+			sqlx::query!(
+				r#"
+				INSERT INTO name_group_members (
+					name,
+					group_id
+				) VALUES ($1, $2)
+				ON CONFLICT DO NOTHING
+				"#,
+				name,
+				group_id,
+			)
+			.execute(&mut *transaction)
+			.await?;
+		}
+	}
+
+	delete_empty_name_groups(&mut transaction).await?;
+	transaction.commit().await
+}
+
+/// Puts the names and all of their existing variants into one group.
+pub async fn merge_name_groups(names: &[String], database_pool: &SqlitePool) -> sqlx::Result<()> {
+	let mut transaction = database_pool.begin().await?;
+	let group_id = sqlx::query_scalar!(
+		r#"
+		INSERT INTO name_groups DEFAULT VALUES
+		RETURNING id
+		"#,
+	)
+	.fetch_one(&mut *transaction)
+	.await?;
+
+	for name in names {
+		sqlx::query!(
+			r#"
+			UPDATE name_group_members
+			SET
+				group_id = $2,
+				manual = TRUE
+			WHERE group_id = (
+				SELECT group_id
+				FROM name_group_members
+				WHERE name = $1
+			)
+			"#,
+			name,
+			group_id,
+		)
+		.execute(&mut *transaction)
+		.await?;
+
+		sqlx::query!(
+			r#"
+			INSERT INTO name_group_members (
+				name,
+				group_id,
+				manual
+			) VALUES ($1, $2, TRUE)
+			ON CONFLICT DO UPDATE
+			SET
+				group_id = $2,
+				manual = TRUE
+			"#,
+			name,
+			group_id,
+		)
+		.execute(&mut *transaction)
+		.await?;
+	}
+
+	delete_empty_name_groups(&mut transaction).await?;
+	transaction.commit().await
+}
+
+/// Takes a name out of its group, this sticks even when regrouping automatically.
+pub async fn split_name_from_group(name: &str, database_pool: &SqlitePool) -> sqlx::Result<()> {
+	let mut transaction = database_pool.begin().await?;
+	let group_id = sqlx::query_scalar!(
+		r#"
+		INSERT INTO name_groups DEFAULT VALUES
+		RETURNING id
+		"#,
+	)
+	.fetch_one(&mut *transaction)
+	.await?;
+
+	sqlx::query!(
+		r#"
+		INSERT INTO name_group_members (
+			name,
+			group_id,
+			manual
+		) VALUES ($1, $2, TRUE)
+		ON CONFLICT DO UPDATE
+		SET
+			group_id = $2,
+			manual = TRUE
+		"#,
+		name,
+		group_id,
+	)
+	.execute(&mut *transaction)
+	.await?;
+
+	delete_empty_name_groups(&mut transaction).await?;
+	transaction.commit().await
+}
+
+async fn delete_empty_name_groups(transaction: &mut Transaction<'_, Sqlite>) -> sqlx::Result<()> {
+	sqlx::query!(
+		r#"
+		DELETE FROM name_groups
+		WHERE id NOT IN (
+			SELECT group_id
+			FROM name_group_members
+		)
+		"#,
+	)
+	.execute(&mut **transaction)
+	.await?;
+	Ok(())
+}
+
+/// Sets the preference and rating of a name and all of its spelling variants at once.
+pub async fn set_group_preference(
+	name: &str,
+	rating: Rating,
+	voter: Option<&str>,
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<Vec<RatingChange>> {
+	let ratings = views::read_name_group(name, database_pool)
+		.await?
+		.into_iter()
+		.map(|name| (name, rating))
+		.collect::<Vec<_>>();
+	set_name_ratings(&ratings, voter, project_id, database_pool).await
+}
+
+pub async fn set_name_rating(
+	name: &str,
+	rating: Rating,
+	voter: Option<&str>,
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<()> {
+	set_name_ratings(&[(name.to_owned(), rating)], voter, project_id, database_pool).await?;
+	Ok(())
+}
+
+/// A name whose rating was changed by [`set_name_ratings`]
+#[derive(Clone, Debug)]
+pub struct RatingChange {
+	pub name: String,
+	pub before: Rating,
+	pub after: Rating,
+}
+
+/// Sets the ratings of all names in a single transaction, so either all or none of them change. Without a preference,
+/// a name is reset to undecided and loses its stars. Every actual change is recorded in the preference history, logged
+/// for [`sync`](crate::sync) and returned.
+pub async fn set_name_ratings(
+	ratings: &[(String, Rating)],
+	voter: Option<&str>,
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<Vec<RatingChange>> {
+	let mut transaction = database_pool.begin().await?;
+	let changes = write_name_ratings(ratings, voter, project_id, &mut transaction).await?;
+	transaction.commit().await?;
+	Ok(changes)
+}
+
+/// A note from an exported file, see [`import_preferences`]
+#[derive(Clone, Debug)]
+pub struct ImportedNote {
+	pub name: String,
+	pub voter: Option<String>,
+	pub note: String,
+	pub created_at: Option<NaiveDateTime>,
+}
+
+/// Sets the ratings like [`set_name_ratings`] and adds the notes in the same transaction, so a failed import doesn't
+/// leave only one of them behind. Returns the changes and the number of notes that weren't there yet.
+pub async fn import_preferences(
+	ratings: &[(String, Rating)],
+	notes: &[ImportedNote],
+	voter: Option<&str>,
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<(Vec<RatingChange>, usize)> {
+	let mut transaction = database_pool.begin().await?;
+	let changes = write_name_ratings(ratings, voter, project_id, &mut transaction).await?;
+
+	let mut imported_notes = 0;
+	for note in notes {
+		if insert_imported_note(note, project_id, &mut transaction).await? {
+			imported_notes += 1;
+		}
+	}
+
+	transaction.commit().await?;
+	Ok((changes, imported_notes))
+}
+
+async fn write_name_ratings(
+	ratings: &[(String, Rating)],
+	voter: Option<&str>,
+	project_id: ProjectId,
+	transaction: &mut Transaction<'_, Sqlite>,
+) -> sqlx::Result<Vec<RatingChange>> {
+	let mut changes = Vec::new();
+	for (name, rating) in ratings {
+		let Some(change) = write_name_rating(name, *rating, voter, project_id, transaction).await? else {
+			continue;
+		};
+
+		sqlx::query!(
+			r#"
+			INSERT INTO sync_operations (
+				device_id,
+				clock,
+				project,
+				name,
+				preference,
+				stars,
+				voter
+			)
+			SELECT
+				sync_state.device_id,
+				coalesce((SELECT max(clock) FROM sync_operations), 0) + 1,
+				projects.name,
+				$2,
+				$3,
+				$4,
+				$5
+			FROM sync_state, projects
+			WHERE projects.id = $1
+			"#,
+			project_id,
+			name,
+			change.after.preference,
+			change.after.stars,
+			voter,
+		)
+		.execute(&mut **transaction)
+		.await?;
+		changes.push(change);
+	}
+	Ok(changes)
+}
+
+/// Returns the change if the rating was different before, after recording it in the preference history
+async fn write_name_rating(
+	name: &str,
+	rating: Rating,
+	voter: Option<&str>,
+	project_id: ProjectId,
+	transaction: &mut Transaction<'_, Sqlite>,
+) -> sqlx::Result<Option<RatingChange>> {
+	let before = read_name_rating(name, project_id, transaction).await?;
+	match rating.preference {
+		Some(preference) => upsert_name_preference(name, preference, rating.stars, project_id, transaction).await?,
+		None => delete_name_preference(name, project_id, transaction).await?,
+	}
+	let after = read_name_rating(name, project_id, transaction).await?;
+	if after == before {
+		return Ok(None);
+	}
+
+	sqlx::query!(
+		r#"
+		INSERT INTO preference_history (
+			project_id,
+			name,
+			old_preference,
+			old_stars,
+			new_preference,
+			new_stars,
+			voter
+		) VALUES ($1, $2, $3, $4, $5, $6, $7)
+		"#,
+		project_id,
+		name,
+		before.preference,
+		before.stars,
+		after.preference,
+		after.stars,
+		voter,
+	)
+	.execute(&mut **transaction)
+	.await?;
+	Ok(Some(RatingChange {
+		name: name.to_owned(),
+		before,
+		after,
+	}))
+}
+
+pub async fn read_sync_device_id(database_pool: &SqlitePool) -> sqlx::Result<String> {
+	sqlx::query_scalar!("SELECT device_id FROM sync_state")
+		.fetch_one(database_pool)
+		.await
+}
+
+/// For a database file that was copied, otherwise both copies would make changes under the same id
+pub async fn renew_sync_device_id(database_pool: &SqlitePool) -> sqlx::Result<String> {
+	sqlx::query_scalar!(
+		r#"
+		UPDATE sync_state
+		SET device_id = lower(hex(randomblob(8)))
+		RETURNING device_id
+		"#
+	)
+	.fetch_one(database_pool)
+	.await
+}
+
+/// The operations after the last export in the order they were recorded, or all of them, together with the id of
+/// the last one for [`mark_sync_exported`]
+pub async fn read_sync_operations(all: bool, database_pool: &SqlitePool) -> sqlx::Result<(Vec<SyncOperation>, i64)> {
+	let mut transaction = database_pool.begin().await?;
+	let operations = sqlx::query_as!(
+		SyncOperation,
+		r#"
+		SELECT
+			device_id,
+			clock,
+			project,
+			name,
+			preference as "preference?: NamePreference",
+			stars as "stars?: u8",
+			voter
+		FROM sync_operations
+		WHERE
+			$1
+			OR id > (SELECT exported_operation FROM sync_state)
+		ORDER BY id ASC
+		"#,
+		all,
+	)
+	.fetch_all(&mut *transaction)
+	.await?;
+	let last_operation = sqlx::query_scalar!(r#"SELECT coalesce(max(id), 0) as "id!: i64" FROM sync_operations"#)
+		.fetch_one(&mut *transaction)
+		.await?;
+	transaction.commit().await?;
+	Ok((operations, last_operation))
+}
+
+pub async fn mark_sync_exported(last_operation: i64, database_pool: &SqlitePool) -> sqlx::Result<()> {
+	sqlx::query!(
+		r#"
+		UPDATE sync_state
+		SET exported_operation = max(exported_operation, $1)
+		"#,
+		last_operation,
+	)
+	.execute(database_pool)
+	.await?;
+	Ok(())
+}
+
+/// The result of [`import_sync_operations`]
+#[derive(Debug, Default)]
+pub struct SyncImport {
+	pub new_operations: usize,
+	/// New operations that lost against a later change of the same name
+	pub superseded_operations: usize,
+	pub changes: Vec<(String, RatingChange)>,
+	/// Projects that don't exist in this database, their operations are only passed on
+	pub unknown_projects: Vec<String>,
+	/// Names that don't exist in this database, their operations are only passed on
+	pub unknown_names: Vec<String>,
+}
+
+/// Applies operations from another copy of the database in a single transaction. Operations that are already known
+/// are ignored, of all operations on the same name only the one with the highest clock is applied.
+pub async fn import_sync_operations(
+	mut operations: Vec<SyncOperation>,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<SyncImport> {
+	// later operations come last, so each name ends up with the rating of the latest one
+	operations.sort_by(|a, b| (a.clock, &a.device_id).cmp(&(b.clock, &b.device_id)));
+
+	let mut transaction = database_pool.begin().await?;
+	let mut import = SyncImport::default();
+	for operation in operations {
+		let inserted = sqlx::query!(
+			r#"
+			INSERT INTO sync_operations (
+				device_id,
+				clock,
+				project,
+				name,
+				preference,
+				stars,
+				voter
+			) VALUES ($1, $2, $3, $4, $5, $6, $7)
+			ON CONFLICT (device_id, clock) DO NOTHING
+			"#,
+			operation.device_id,
+			operation.clock,
+			operation.project,
+			operation.name,
+			operation.preference,
+			operation.stars,
+			operation.voter,
+		)
+		.execute(&mut *transaction)
+		.await?
+		.rows_affected();
+		if inserted == 0 {
+			continue;
+		}
+		import.new_operations += 1;
+
+		let latest = sqlx::query!(
+			r#"
+			SELECT
+				device_id,
+				clock
+			FROM sync_operations
+			WHERE
+				project = $1
+				AND name = $2
+			ORDER BY clock DESC, device_id DESC
+			LIMIT 1
+			"#,
+			operation.project,
+			operation.name,
+		)
+		.fetch_one(&mut *transaction)
+		.await?;
+		if (latest.device_id.as_str(), latest.clock) != (operation.device_id.as_str(), operation.clock) {
+			import.superseded_operations += 1;
+			continue;
+		}
+
+		let Some(project_id) = sqlx::query_scalar!("SELECT id FROM projects WHERE name = $1", operation.project)
+			.fetch_optional(&mut *transaction)
+			.await?
+		else {
+			if !import.unknown_projects.contains(&operation.project) {
+				import.unknown_projects.push(operation.project);
+			}
+			continue;
+		};
+		let name_exists = sqlx::query_scalar!(
+			r#"
+			SELECT EXISTS (SELECT 1 FROM names WHERE name = $1) as "exists!: bool"
+			"#,
+			operation.name,
+		)
+		.fetch_one(&mut *transaction)
+		.await?;
+		if !name_exists {
+			if !import.unknown_names.contains(&operation.name) {
+				import.unknown_names.push(operation.name);
+			}
+			continue;
+		}
+
+		let rating = Rating {
+			preference: operation.preference,
+			stars: operation.stars,
+		};
+		if let Some(change) = write_name_rating(
+			&operation.name,
+			rating,
+			operation.voter.as_deref(),
+			project_id,
+			&mut transaction,
+		)
+		.await?
+		{
+			import.changes.push((operation.project, change));
+		}
+	}
+
+	transaction.commit().await?;
+	Ok(import)
+}
+
+/// Keeps the ratings of a single voter next to the shared ratings of the project, e.g. when merging with
+/// [`MergeStrategy::SeparateVoters`](crate::preference_merge::MergeStrategy::SeparateVoters). Without a preference, the
+/// rating of the voter is removed.
+pub async fn set_voter_ratings(
+	voter: &str,
+	ratings: &[(String, Rating)],
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<()> {
+	let mut transaction = database_pool.begin().await?;
+	for (name, Rating { preference, stars }) in ratings {
+		match preference {
+			Some(preference) => {
+				sqlx::query!(
+					r#"
+					INSERT INTO voter_ratings (
+						project_id,
+						name,
+						voter,
+						preference,
+						stars
+					) VALUES ($1, $2, $3, $4, $5)
+					ON CONFLICT DO UPDATE
+					SET
+						preference = excluded.preference,
+						stars = excluded.stars
+					"#,
+					project_id,
+					name,
+					voter,
+					preference,
+					stars,
+				)
+				.execute(&mut *transaction)
+				.await?;
+			}
+			None => {
+				sqlx::query!(
+					r#"
+					DELETE FROM voter_ratings
+					WHERE
+						project_id = $1
+						AND name = $2
+						AND voter = $3
+					"#,
+					project_id,
+					name,
+					voter,
+				)
+				.execute(&mut *transaction)
+				.await?;
+			}
+		}
+	}
+	transaction.commit().await
+}
+
+/// The ratings of the project with the given name in another database file, e.g. the copy on the laptop of the other
+/// parent. The other database needs to be migrated to the same version. Returns `None` if it has no such project.
+///
+/// The file has to exist, otherwise an empty database would be created for a mistyped path instead of failing.
+pub async fn read_other_database_ratings(
+	other_database: &Path,
+	project_name: &str,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<Option<Vec<(String, Rating)>>> {
+	// attaching only affects a single connection of the pool
+	let mut connection = database_pool.acquire().await?;
+	sqlx::query("ATTACH DATABASE $1 AS other")
+		.bind(other_database.to_string_lossy())
+		.execute(&mut *connection)
+		.await?;
+
+	// not checked at compile time since the attached database is unknown to the query macros
+	let ratings = async {
+		let project_exists =
+			sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM other.projects WHERE name = $1)")
+				.bind(project_name)
+				.fetch_one(&mut *connection)
+				.await?;
+		if !project_exists {
+			return Ok(None);
+		}
+
+		let ratings = sqlx::query_as::<_, (String, NamePreference, Option<u8>)>(
+			r"
+			SELECT
+				rating.name,
+				rating.preference,
+				rating.stars
+			FROM other.name_preference AS rating
+				INNER JOIN other.projects AS project ON project.id = rating.project_id
+			WHERE project.name = $1
+			ORDER BY rating.name ASC
+			",
+		)
+		.bind(project_name)
+		.fetch_all(&mut *connection)
+		.await?;
+		Ok(Some(
+			ratings
+				.into_iter()
+				.map(|(name, preference, stars)| {
+					let rating = Rating {
+						preference: Some(preference),
+						stars,
+					};
+					(name, rating)
+				})
+				.collect(),
+		))
+	}
+	.await;
+
+	sqlx::query("DETACH DATABASE other").execute(&mut *connection).await?;
+	ratings
+}
+
+pub type ProjectId = i64;
+
+/// A baby that names are searched for, with its own preferences and family details
+#[derive(Clone, Debug)]
+pub struct Project {
+	pub id: ProjectId,
+	pub name: String,
+	/// The family surname that first names are checked against
+	pub surname: Option<String>,
+	/// Middle name that is part of the initials a first name is checked for
+	pub middle_name: Option<String>,
+	/// Only names for this gender are listed, `Both` doesn't restrict them
+	pub gender: Gender,
+	pub due_date: Option<NaiveDate>,
+}
+
+pub async fn read_projects(database_pool: &SqlitePool) -> sqlx::Result<Vec<Project>> {
+	sqlx::query_as!(
+		Project,
+		r#"
+		SELECT
+			id,
+			name,
+			surname,
+			middle_name,
+			gender as "gender: Gender",
+			due_date as "due_date: NaiveDate"
+		FROM projects
+		ORDER BY name ASC
+		"#,
+	)
+	.fetch_all(database_pool)
+	.await
+}
+
+pub async fn read_project_by_name(name: &str, database_pool: &SqlitePool) -> sqlx::Result<Option<Project>> {
+	sqlx::query_as!(
+		Project,
+		r#"
+		SELECT
+			id,
+			name,
+			surname,
+			middle_name,
+			gender as "gender: Gender",
+			due_date as "due_date: NaiveDate"
+		FROM projects
+		WHERE name = $1
+		"#,
+		name,
+	)
+	.fetch_optional(database_pool)
+	.await
+}
+
+pub async fn insert_project(name: &str, database_pool: &SqlitePool) -> sqlx::Result<ProjectId> {
+	sqlx::query_scalar!(
+		r#"
+		INSERT INTO projects (name)
+		VALUES ($1)
+		RETURNING id
+		"#,
+		name,
+	)
+	.fetch_one(database_pool)
+	.await
+}
+
+pub async fn update_project(
+	Project {
+		id,
+		name,
+		surname,
+		middle_name,
+		gender,
+		due_date,
+	}: &Project,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<()> {
+	sqlx::query!(
+		r#"
+		UPDATE projects
+		SET
+			name = $2,
+			surname = $3,
+			middle_name = $4,
+			gender = $5,
+			due_date = $6
+		WHERE id = $1
+		"#,
+		id,
+		name,
+		surname,
+		middle_name,
+		gender,
+		due_date,
+	)
+	.execute(database_pool)
+	.await?;
+	Ok(())
+}
+
+/// Deletes a project together with all of its preferences
+pub async fn delete_project(project_id: ProjectId, database_pool: &SqlitePool) -> sqlx::Result<()> {
+	sqlx::query!(
+		r#"
+		DELETE FROM projects
+		WHERE id = $1
+		"#,
+		project_id,
+	)
+	.execute(database_pool)
+	.await?;
+	Ok(())
+}
+
+/// Initials blocked in addition to the bundled blocklist
+pub async fn read_blocked_initials(database_pool: &SqlitePool) -> sqlx::Result<Vec<String>> {
+	sqlx::query_scalar!(
+		r#"
+		SELECT initials
+		FROM blocked_initials
+		ORDER BY initials ASC
+		"#,
+	)
+	.fetch_all(database_pool)
+	.await
+}
+
+/// The bundled blocklist together with the user defined initials
+pub async fn read_initials_blocklist(database_pool: &SqlitePool) -> sqlx::Result<InitialsBlocklist> {
+	Ok(InitialsBlocklist::with_additional(
+		read_blocked_initials(database_pool).await?,
+	))
+}
+
+pub async fn insert_blocked_initials(initials: &str, database_pool: &SqlitePool) -> sqlx::Result<()> {
+	sqlx::query!(
+		r#"
+		INSERT INTO blocked_initials (initials)
+		VALUES ($1)
+		ON CONFLICT DO NOTHING
+		"#,
+		initials,
+	)
+	.execute(database_pool)
+	.await?;
+	Ok(())
+}
+
+pub async fn delete_blocked_initials(initials: &str, database_pool: &SqlitePool) -> sqlx::Result<()> {
+	sqlx::query!(
+		r#"
+		DELETE FROM blocked_initials
+		WHERE initials = $1
+		"#,
+		initials,
+	)
+	.execute(database_pool)
+	.await?;
+	Ok(())
+}
+
+/// Names of older siblings, new names should go well with them
+pub async fn read_siblings(project_id: ProjectId, database_pool: &SqlitePool) -> sqlx::Result<Vec<String>> {
+	sqlx::query_scalar!(
+		r#"
+		SELECT name
+		FROM siblings
+		WHERE project_id = $1
+		ORDER BY name ASC
+		"#,
+		project_id,
+	)
+	.fetch_all(database_pool)
+	.await
+}
+
+pub async fn insert_sibling(name: &str, project_id: ProjectId, database_pool: &SqlitePool) -> sqlx::Result<()> {
+	sqlx::query!(
+		r#"
+		INSERT INTO siblings (
+			project_id,
+			name
+		) VALUES ($1, $2)
+		ON CONFLICT DO NOTHING
+		"#,
+		project_id,
+		name,
+	)
+	.execute(database_pool)
+	.await?;
+	Ok(())
+}
+
+pub async fn delete_sibling(name: &str, project_id: ProjectId, database_pool: &SqlitePool) -> sqlx::Result<()> {
+	sqlx::query!(
+		r#"
+		DELETE FROM siblings
+		WHERE
+			project_id = $1
+			AND name = $2
 		"#,
+		project_id,
 		name,
 	)
 	.execute(database_pool)
@@ -1,72 +1,1189 @@
+use crate::combinations::Combination;
 use crate::csv_parser::Gender;
-use crate::database::NamePreference;
-use sqlx::SqlitePool;
+use crate::database::{MAXIMUM_STARS, NamePreference, ProjectId, Rating};
+use crate::name_groups::are_variants;
+use crate::name_metadata::{Calendar, NameDay};
+use crate::name_normalization::fold_name;
+use crate::name_structure::Language;
+use crate::name_structure::StructureFilter;
+use crate::nicknames::derive_nicknames;
+use crate::phonetics::{PhoneticAlgorithm, PhoneticCode, phonetic_codes};
+use crate::popularity::{BirthShare, year_of_source};
+use crate::search_query::{Comparison, Predicate, SearchQuery, Term};
+use crate::similarity::{Similarity, levenshtein, trigrams};
+use chrono::{Local, NaiveDateTime};
+use sqlx::types::Json;
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use std::cmp::Reverse;
+use std::fmt::{Display, Formatter};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, sqlx::FromRow)]
 pub struct NameWithPreference {
 	pub name: String,
 	pub gender: Gender,
 	pub preference: Option<NamePreference>,
+	/// From 1 to [`crate::database::MAXIMUM_STARS`]
+	pub stars: Option<u8>,
+	/// Share of the children of a year that get the name, averaged over all sources
+	pub birth_share: Option<BirthShare>,
+	/// Other spellings of the same name
+	pub variants: Json<Vec<String>>,
+	/// There are notes about the name in the project
+	pub has_notes: bool,
+	/// Tags of the name in the project
+	pub tags: Json<Vec<String>>,
+	/// A favorite whose re-review interval has passed since it was last confirmed
+	pub due_for_review: bool,
+}
+
+impl NameWithPreference {
+	pub fn rating(&self) -> Rating {
+		Rating {
+			preference: self.preference,
+			stars: self.stars,
+		}
+	}
+}
+
+pub async fn read_one(
+	name: &str,
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<NameWithPreference> {
+	let mut query_builder = select_names(project_id);
+	query_builder.push("names.name = ").push_bind(name.to_owned());
+	query_builder
+		.build_query_as::<NameWithPreference>()
+		.fetch_one(database_pool)
+		.await
+}
+
+#[derive(Clone, Debug)]
+pub enum NameSearch {
+	Query(SearchQuery),
+	/// Names sharing at least one phonetic code with the given name
+	SoundsLike(String),
+	/// Typo tolerant search, ordered by how well the names match instead of alphabetically
+	Fuzzy(String),
+}
+
+impl Default for NameSearch {
+	fn default() -> Self {
+		Self::Query(SearchQuery::default())
+	}
+}
+
+/// Which names to include depending on their preference in the project
+#[derive(Clone, Copy, Debug)]
+#[expect(clippy::struct_excessive_bools)]
+pub struct PreferenceSelection {
+	pub favorite: bool,
+	pub nogo: bool,
+	pub undecided: bool,
+	/// Only include the favorites that are due for a re-review
+	pub only_due_for_review: bool,
+}
+
+impl PreferenceSelection {
+	pub const ALL: Self = Self {
+		favorite: true,
+		nogo: true,
+		undecided: true,
+		only_due_for_review: false,
+	};
+
+	pub const UNDECIDED: Self = Self {
+		favorite: false,
+		nogo: false,
+		undecided: true,
+		only_due_for_review: false,
+	};
+
+	pub const DUE_FOR_REVIEW: Self = Self {
+		favorite: true,
+		nogo: false,
+		undecided: false,
+		only_due_for_review: true,
+	};
+}
+
+/// Favorites whose re-review interval has passed since they were last confirmed
+const DUE_FOR_REVIEW_CONDITION: &str = r"
+	name_preference.preference = 'favorite'
+	AND datetime(
+		name_preference.confirmed_at,
+		'+' || name_preference.review_interval_days || ' days'
+	) <= CURRENT_TIMESTAMP";
+
+/// The columns of [`NameWithPreference`] for all names in the project, to be followed by a condition
+fn select_names(project_id: ProjectId) -> QueryBuilder<'static, Sqlite> {
+	let mut query_builder = QueryBuilder::new(
+		r"
+		WITH gender_totals AS MATERIALIZED (
+			SELECT source, gender, SUM(count) as total
+			FROM name_records
+			GROUP BY source, gender
+		)
+		SELECT
+			names.name,
+			names.gender,
+			name_preference.preference,
+			name_preference.stars,
+			(
+				-- boys and girls are counted separately, each is about half of the children
+				SELECT AVG(source_share) FROM (
+					SELECT SUM(name_records.count * 1.0 / gender_totals.total) / 2 as source_share
+					FROM name_records
+					JOIN gender_totals
+						ON gender_totals.source = name_records.source
+						AND gender_totals.gender = name_records.gender
+					WHERE name_records.name = names.name
+					GROUP BY name_records.source
+				)
+			) as birth_share,
+			(
+				SELECT json_group_array(other.name)
+				FROM name_group_members as own
+				JOIN name_group_members as other
+					ON own.group_id = other.group_id
+				WHERE
+					own.name = names.name
+					AND other.name != names.name
+			) as variants,
+			EXISTS (
+				SELECT 1 FROM name_notes WHERE name_notes.name = names.name AND name_notes.project_id = projects.id
+			) as has_notes,
+			(
+				SELECT json_group_array(tags.name)
+				FROM name_tags
+				JOIN tags
+					ON tags.id = name_tags.tag_id
+				WHERE
+					name_tags.name = names.name
+					AND tags.project_id = projects.id
+			) as tags,
+			coalesce(",
+	);
+	query_builder
+		.push(DUE_FOR_REVIEW_CONDITION)
+		.push(
+			r", FALSE) as due_for_review
+		FROM names
+		JOIN projects
+			ON projects.id = ",
+		)
+		.push_bind(project_id)
+		.push(
+			r"
+		LEFT JOIN name_preference
+			ON names.name = name_preference.name
+			AND name_preference.project_id = projects.id
+		WHERE ",
+		);
+	query_builder
+}
+
+#[expect(clippy::too_many_arguments)]
+pub async fn read_all_names(
+	gender: Gender,
+	preferences: PreferenceSelection,
+	search: &NameSearch,
+	structure: &StructureFilter,
+	exclude_similar_to_siblings: bool,
+	tag: Option<&str>,
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<Vec<NameWithPreference>> {
+	let siblings = if exclude_similar_to_siblings {
+		super::read_siblings(project_id, database_pool).await?
+	} else {
+		Vec::new()
+	};
+
+	let mut query_builder = select_names(project_id);
+	query_builder.push("CASE ").push_bind(gender).push(
+		r"
+				WHEN 'both' THEN TRUE
+				WHEN 'female' THEN names.gender != 'male'
+				WHEN 'male' THEN names.gender != 'female'
+			END
+			AND CASE projects.gender
+				WHEN 'both' THEN TRUE
+				WHEN 'female' THEN names.gender != 'male'
+				WHEN 'male' THEN names.gender != 'female'
+			END
+			AND ",
+	);
+	push_preference_selection(&mut query_builder, preferences);
+	match search {
+		NameSearch::Query(query) => push_search_query(&mut query_builder, query, project_id),
+		NameSearch::SoundsLike(name) => push_sounds_like(&mut query_builder, name),
+		NameSearch::Fuzzy(term) => push_fuzzy(&mut query_builder, term),
+	}
+	if let Some(tag) = tag {
+		let tag_query = SearchQuery {
+			terms: vec![Term {
+				negated: false,
+				predicate: Predicate::Tag(tag.to_owned()),
+			}],
+		};
+		push_search_query(&mut query_builder, &tag_query, project_id);
+	}
+	if !siblings.is_empty() {
+		// siblings don't have to be part of the datasets, so only their few codes are calculated here
+		query_builder.push(" AND NOT ");
+		push_shares_phonetic_code(
+			&mut query_builder,
+			siblings.iter().flat_map(|sibling| phonetic_codes(sibling)).collect(),
+		);
+	}
+	query_builder.push(" ORDER BY names.name ASC");
+
+	let mut names = query_builder
+		.build_query_as::<NameWithPreference>()
+		.fetch_all(database_pool)
+		.await?;
+
+	// Syllables and unicode aware comparisons can't be done in SQLite
+	names.retain(|name| structure.matches(&name.name));
+
+	// names that sound like one of the siblings were already excluded above, see [`crate::siblings::too_similar`]
+	names.retain(|name| !siblings.iter().any(|sibling| are_variants(&name.name, sibling)));
+
+	if let NameSearch::Fuzzy(term) = search {
+		// only limited after all filters were applied, otherwise they could filter out every one of the best matches
+		const MAXIMUM_MATCHES: usize = 500;
+
+		let term_trigrams = trigrams(term);
+		names.sort_by_cached_key(|name| {
+			let shared_trigrams = trigrams(&name.name)
+				.iter()
+				.filter(|trigram| term_trigrams.contains(trigram))
+				.count();
+			(Reverse(shared_trigrams), levenshtein(term, &name.name))
+		});
+		names.truncate(MAXIMUM_MATCHES);
+	}
+
+	Ok(names)
+}
+
+fn push_preference_selection(query_builder: &mut QueryBuilder<'_, Sqlite>, preferences: PreferenceSelection) {
+	query_builder
+		.push("((")
+		.push_bind(preferences.favorite)
+		.push(" AND preference = 'favorite' AND (NOT ")
+		.push_bind(preferences.only_due_for_review)
+		.push(" OR (")
+		.push(DUE_FOR_REVIEW_CONDITION)
+		.push("))) OR (")
+		.push_bind(preferences.nogo)
+		.push(" AND preference = 'no_go') OR (")
+		.push_bind(preferences.undecided)
+		.push(" AND preference IS NULL))");
+}
+
+/// Appends every term of the query as an `AND` condition on the `names` and `name_preference` tables, metadata and
+/// notes are looked up with subqueries.
+fn push_search_query(query_builder: &mut QueryBuilder<'_, Sqlite>, query: &SearchQuery, project_id: ProjectId) {
+	for Term { negated, predicate } in &query.terms {
+		query_builder.push(if *negated { " AND NOT (" } else { " AND (" });
+		match predicate {
+			Predicate::Contains(text) => {
+				query_builder
+					.push(r"names.folded_name LIKE '%' || ")
+					.push_bind(escape_like(&fold_name(text)))
+					.push(r" || '%' ESCAPE '\'")
+					.push(" OR names.name IN (SELECT name FROM name_notes WHERE project_id = ")
+					.push_bind(project_id)
+					.push(r" AND note LIKE '%' || ")
+					.push_bind(escape_like(text))
+					.push(r" || '%' ESCAPE '\')");
+			}
+			Predicate::NameContains(text) => {
+				query_builder
+					.push(r"names.folded_name LIKE '%' || ")
+					.push_bind(escape_like(&fold_name(text)))
+					.push(r" || '%' ESCAPE '\'");
+			}
+			Predicate::StartsWith(text) => {
+				query_builder
+					.push("names.folded_name LIKE ")
+					.push_bind(escape_like(&fold_name(text)))
+					.push(r" || '%' ESCAPE '\'");
+			}
+			Predicate::EndsWith(text) => {
+				query_builder
+					.push(r"names.folded_name LIKE '%' || ")
+					.push_bind(escape_like(&fold_name(text)))
+					.push(r" ESCAPE '\'");
+			}
+			Predicate::Length(Comparison { operator, value }) => {
+				query_builder
+					.push("length(names.name) ")
+					.push(operator.as_sql())
+					.push_bind(*value);
+			}
+			Predicate::Stars(Comparison { operator, value }) => {
+				query_builder
+					.push("CASE WHEN name_preference.preference = 'favorite' THEN coalesce(name_preference.stars, ")
+					.push_bind(MAXIMUM_STARS)
+					.push(") ELSE 0 END ")
+					.push(operator.as_sql())
+					.push_bind(*value);
+			}
+			Predicate::Popularity(Comparison { operator, value }) => {
+				query_builder
+					.push("coalesce((SELECT SUM(count) FROM name_records WHERE name_records.name = names.name), 0) ")
+					.push(operator.as_sql())
+					.push_bind(*value);
+			}
+			Predicate::Gender(gender) => {
+				query_builder.push(match gender {
+					Gender::Female => "names.gender != 'male'",
+					Gender::Male => "names.gender != 'female'",
+					Gender::Both => "names.gender = 'both'",
+				});
+			}
+			Predicate::Preference(preference) => {
+				query_builder
+					.push("name_preference.preference IS ")
+					.push_bind(*preference);
+			}
+			Predicate::Origin(text) => {
+				query_builder
+					.push(r"names.name IN (SELECT name FROM name_metadata WHERE folded_origin LIKE '%' || ")
+					.push_bind(escape_like(&fold_name(text)))
+					.push(r" || '%' ESCAPE '\')");
+			}
+			Predicate::Meaning(text) => {
+				query_builder
+					.push(r"names.name IN (SELECT name FROM name_metadata WHERE folded_meaning LIKE '%' || ")
+					.push_bind(escape_like(&fold_name(text)))
+					.push(r" || '%' ESCAPE '\')");
+			}
+			Predicate::Tag(tag) => {
+				query_builder
+					.push("names.name IN (SELECT name_tags.name FROM name_tags JOIN tags ON tags.id = name_tags.tag_id WHERE tags.project_id = ")
+					.push_bind(project_id)
+					.push(" AND tags.name = ")
+					.push_bind(tag.clone())
+					.push(")");
+			}
+			Predicate::NameDay(months) => {
+				query_builder.push("names.name IN (SELECT name FROM name_days WHERE month IN (");
+				let mut separated = query_builder.separated(", ");
+				for month in months {
+					separated.push_bind(*month);
+				}
+				query_builder.push("))");
+			}
+		}
+		query_builder.push(")");
+	}
 }
 
-pub async fn read_one(name: &str, database_pool: &SqlitePool) -> sqlx::Result<NameWithPreference> {
+fn push_sounds_like(query_builder: &mut QueryBuilder<'_, Sqlite>, name: &str) {
+	query_builder.push(" AND ");
+	push_shares_phonetic_code(query_builder, phonetic_codes(name));
+}
+
+/// Condition for names that share at least one of the codes according to their stored phonetic codes
+fn push_shares_phonetic_code(query_builder: &mut QueryBuilder<'_, Sqlite>, codes: Vec<PhoneticCode>) {
+	if codes.is_empty() {
+		query_builder.push("FALSE");
+		return;
+	}
+
+	query_builder.push("names.name IN (SELECT name FROM phonetic_codes WHERE ");
+	let mut conditions = query_builder.separated(" OR ");
+	for code in codes {
+		conditions
+			.push("(algorithm = ")
+			.push_bind_unseparated(code.algorithm)
+			.push_unseparated(" AND code = ")
+			.push_bind_unseparated(code.code)
+			.push_unseparated(")");
+	}
+	query_builder.push(")");
+}
+
+fn push_fuzzy(query_builder: &mut QueryBuilder<'_, Sqlite>, term: &str) {
+	let trigrams = trigrams(term);
+	if trigrams.is_empty() {
+		// the trigram index can't find anything shorter than three characters
+		query_builder
+			.push(r" AND names.folded_name LIKE '%' || ")
+			.push_bind(escape_like(&fold_name(term)))
+			.push(r" || '%' ESCAPE '\'");
+		return;
+	}
+
+	query_builder
+		.push(" AND names.name IN (SELECT name FROM names_trigram WHERE names_trigram MATCH ")
+		.push_bind(trigram_match_expression(&trigrams))
+		.push(")");
+}
+
+/// Full text query matching anything that shares at least one trigram, so a single typo still leaves most of them
+/// matching.
+fn trigram_match_expression(trigrams: &[String]) -> String {
+	trigrams
+		.iter()
+		.map(|trigram| format!(r#""{}""#, trigram.replace('"', r#""""#)))
+		.collect::<Vec<_>>()
+		.join(" OR ")
+}
+
+fn escape_like(text: &str) -> String {
+	text.replace('\\', r"\\").replace('%', r"\%").replace('_', r"\_")
+}
+
+#[derive(Clone, Debug)]
+pub struct NameRecordStatistics {
+	pub source: String,
+	pub gender: Gender,
+	pub count: i64,
+	pub gender_total: i64,
+	pub source_total: i64,
+}
+
+pub async fn read_name_statistics(name: &str, database_pool: &SqlitePool) -> sqlx::Result<Vec<NameRecordStatistics>> {
 	sqlx::query_as!(
-		NameWithPreference,
+		NameRecordStatistics,
 		r#"
 		SELECT
-			names.name as "name!",
-			gender as "gender!: Gender",
-			name_preference.preference as "preference?: NamePreference"
-		FROM names
-			LEFT JOIN name_preference ON
-				names.name = name_preference.name
+			name_records.source as "source!",
+			name_records.gender as "gender!: Gender",
+			name_records.count as "count!",
+			gender_totals.total as "gender_total!: i64",
+			source_totals.total as "source_total!: i64"
+		FROM name_records
+		JOIN (
+			SELECT source, gender, SUM(count) as total
+			FROM name_records
+			GROUP BY source, gender
+		) as gender_totals
+			ON name_records.source = gender_totals.source
+			AND name_records.gender = gender_totals.gender
+		JOIN (
+			SELECT source, SUM(count) as total
+			FROM name_records
+			GROUP BY source
+		) as source_totals
+			ON name_records.source = source_totals.source
 		WHERE
-			names.name = $1
+			name_records.name = $1
+		ORDER BY name_records.source ASC, name_records.gender ASC
 		"#,
 		name,
 	)
-	.fetch_one(database_pool)
+	.fetch_all(database_pool)
 	.await
 }
 
-pub async fn read_all_names(
-	gender: Gender,
-	include_favorite: bool,
-	include_nogo: bool,
-	include_undecided: bool,
-	name_contains: Option<&str>,
+/// Stored phonetic codes of a name, empty if it isn't part of the datasets
+pub async fn read_phonetic_codes(name: &str, database_pool: &SqlitePool) -> sqlx::Result<Vec<PhoneticCode>> {
+	sqlx::query_as!(
+		PhoneticCode,
+		r#"
+		SELECT
+			algorithm as "algorithm: PhoneticAlgorithm",
+			code
+		FROM phonetic_codes
+		WHERE name = $1
+		"#,
+		name,
+	)
+	.fetch_all(database_pool)
+	.await
+}
+
+/// Average birth year of a name, weighted by its share of all births of each year.
+///
+/// `None` if the name isn't part of any dataset with a year in its filename.
+pub async fn read_popularity_era(name: &str, database_pool: &SqlitePool) -> sqlx::Result<Option<u16>> {
+	let (weighted_years, total_weight) = read_name_statistics(name, database_pool)
+		.await?
+		.iter()
+		.filter_map(|statistics| {
+			let year = year_of_source(&statistics.source)?;
+			let share = BirthShare::new(statistics.count, statistics.source_total)?;
+			Some((f64::from(year), share.percent()))
+		})
+		.fold((0.0, 0.0), |(weighted_years, total_weight), (year, weight)| {
+			(weighted_years + year * weight, total_weight + weight)
+		});
+
+	if total_weight <= 0.0 {
+		return Ok(None);
+	}
+
+	#[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+	Ok(Some((weighted_years / total_weight).round() as u16))
+}
+
+#[derive(Clone, Debug)]
+pub struct SimilarName {
+	pub name: String,
+	pub similarity: Similarity,
+}
+
+/// The most similar names, sorted by edit distance and then by the longest common substring.
+///
+/// Instead of comparing against every name, only those sharing a trigram with the name are considered.
+pub async fn read_similar_names(
+	name: &str,
+	limit: usize,
 	database_pool: &SqlitePool,
-) -> sqlx::Result<Vec<NameWithPreference>> {
+) -> sqlx::Result<Vec<SimilarName>> {
+	const MAXIMUM_CANDIDATES: i64 = 1000;
+
+	let trigrams = trigrams(name);
+	let candidates = if trigrams.is_empty() {
+		// too short for the trigram index, but there also aren't many names that are that short
+		sqlx::query_scalar!(
+			r#"
+			SELECT name
+			FROM names
+			WHERE
+				length(name) <= length($1) + 2
+				AND name != $1
+			"#,
+			name,
+		)
+		.fetch_all(database_pool)
+		.await?
+	} else {
+		let match_expression = trigram_match_expression(&trigrams);
+		sqlx::query_scalar!(
+			r#"
+			SELECT name as "name!: String"
+			FROM names_trigram
+			WHERE
+				names_trigram MATCH $1
+				AND name != $2
+			ORDER BY rank
+			LIMIT $3
+			"#,
+			match_expression,
+			name,
+			MAXIMUM_CANDIDATES,
+		)
+		.fetch_all(database_pool)
+		.await?
+	};
+
+	let mut similar_names = candidates
+		.into_iter()
+		.map(|candidate| SimilarName {
+			similarity: Similarity::between(name, &candidate),
+			name: candidate,
+		})
+		.collect::<Vec<_>>();
+	similar_names.sort_by_key(|SimilarName { name, similarity }| {
+		(
+			similarity.levenshtein,
+			Reverse(similarity.longest_common_substring),
+			name.clone(),
+		)
+	});
+	similar_names.truncate(limit);
+
+	Ok(similar_names)
+}
+
+#[derive(Clone, Debug)]
+pub struct NameNote {
+	pub id: i64,
+	pub voter: Option<String>,
+	pub note: String,
+	/// UTC
+	pub created_at: NaiveDateTime,
+}
+
+impl Display for NameNote {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+		let created_at = self.created_at.and_utc().with_timezone(&Local);
+		write!(formatter, "{}", created_at.format("%Y-%m-%d %H:%M"))?;
+		if let Some(voter) = &self.voter {
+			write!(formatter, " {voter}")?;
+		}
+		write!(formatter, ": {}", self.note)
+	}
+}
+
+/// Oldest notes first
+pub async fn read_name_notes(
+	name: &str,
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<Vec<NameNote>> {
 	sqlx::query_as!(
-		NameWithPreference,
+		NameNote,
 		r#"
 		SELECT
-			names.name as "name!",
-			gender as "gender!: Gender",
-			name_preference.preference as "preference: NamePreference"
-		FROM names
-		LEFT JOIN name_preference
-			ON names.name = name_preference.name
+			id,
+			voter,
+			note,
+			created_at as "created_at: NaiveDateTime"
+		FROM name_notes
 		WHERE
-			CASE $1
-				WHEN 'both' THEN TRUE
-				WHEN 'female' THEN gender != 'male'
-				WHEN 'male' THEN gender != 'female'
-			END
-			AND (
-				($2 AND preference = 'favorite')
-				OR ($3 AND preference = 'no_go')
-				OR ($4 AND preference IS NULL)
-			)
-			AND ($5 IS NULL OR (names.name LIKE ('%' || $5 || '%')))
-		ORDER BY names.name ASC
+			project_id = $1
+			AND name = $2
+		ORDER BY created_at ASC, id ASC
+		"#,
+		project_id,
+		name,
+	)
+	.fetch_all(database_pool)
+	.await
+}
+
+#[derive(Clone, Debug)]
+pub struct NameRating {
+	pub name: String,
+	pub preference: NamePreference,
+	pub stars: Option<u8>,
+}
+
+impl NameRating {
+	pub fn rating(&self) -> Rating {
+		Rating {
+			preference: Some(self.preference),
+			stars: self.stars,
+		}
+	}
+}
+
+/// All names of the project that aren't undecided anymore, alphabetically
+pub async fn read_name_ratings(project_id: ProjectId, database_pool: &SqlitePool) -> sqlx::Result<Vec<NameRating>> {
+	sqlx::query_as!(
+		NameRating,
+		r#"
+		SELECT
+			name,
+			preference as "preference: NamePreference",
+			stars as "stars?: u8"
+		FROM name_preference
+		WHERE project_id = $1
+		ORDER BY name ASC
+		"#,
+		project_id,
+	)
+	.fetch_all(database_pool)
+	.await
+}
+
+#[derive(Clone, Debug)]
+pub struct VoterRating {
+	pub voter: String,
+	pub preference: NamePreference,
+	pub stars: Option<u8>,
+}
+
+impl VoterRating {
+	pub fn rating(&self) -> Rating {
+		Rating {
+			preference: Some(self.preference),
+			stars: self.stars,
+		}
+	}
+}
+
+/// The ratings of single voters that were kept separately when merging, see [`super::set_voter_ratings`]
+pub async fn read_voter_ratings(
+	name: &str,
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<Vec<VoterRating>> {
+	sqlx::query_as!(
+		VoterRating,
+		r#"
+		SELECT
+			voter,
+			preference as "preference: NamePreference",
+			stars as "stars?: u8"
+		FROM voter_ratings
+		WHERE
+			project_id = $1
+			AND name = $2
+		ORDER BY voter ASC
+		"#,
+		project_id,
+		name,
+	)
+	.fetch_all(database_pool)
+	.await
+}
+
+#[derive(Clone, Debug)]
+pub struct PreferenceChange {
+	pub id: i64,
+	pub name: String,
+	pub old_preference: Option<NamePreference>,
+	pub old_stars: Option<u8>,
+	pub new_preference: Option<NamePreference>,
+	pub new_stars: Option<u8>,
+	pub voter: Option<String>,
+	/// UTC
+	pub changed_at: NaiveDateTime,
+}
+
+impl PreferenceChange {
+	pub fn old_rating(&self) -> Rating {
+		Rating {
+			preference: self.old_preference,
+			stars: self.old_stars,
+		}
+	}
+
+	/// The rating the name had right after the change
+	pub fn new_rating(&self) -> Rating {
+		Rating {
+			preference: self.new_preference,
+			stars: self.new_stars,
+		}
+	}
+
+	pub fn local_changed_at(&self) -> String {
+		let changed_at = self.changed_at.and_utc().with_timezone(&Local);
+		changed_at.format("%Y-%m-%d %H:%M").to_string()
+	}
+}
+
+impl Display for PreferenceChange {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(formatter, "{}", self.local_changed_at())?;
+		if let Some(voter) = &self.voter {
+			write!(formatter, " {voter}")?;
+		}
+		write!(formatter, ": {} → {}", self.old_rating(), self.new_rating())
+	}
+}
+
+/// Oldest changes first, of a single name or of all names in the project
+pub async fn read_preference_history(
+	name: Option<&str>,
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<Vec<PreferenceChange>> {
+	sqlx::query_as!(
+		PreferenceChange,
+		r#"
+		SELECT
+			id,
+			name,
+			old_preference as "old_preference?: NamePreference",
+			old_stars as "old_stars?: u8",
+			new_preference as "new_preference?: NamePreference",
+			new_stars as "new_stars?: u8",
+			voter,
+			changed_at as "changed_at: NaiveDateTime"
+		FROM preference_history
+		WHERE
+			project_id = $1
+			AND ($2 IS NULL OR name = $2)
+		ORDER BY changed_at ASC, id ASC
 		"#,
-		gender,
-		include_favorite,
-		include_nogo,
-		include_undecided,
-		name_contains,
+		project_id,
+		name,
 	)
 	.fetch_all(database_pool)
 	.await
 }
+
+pub async fn read_preference_change(
+	id: i64,
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<Option<PreferenceChange>> {
+	sqlx::query_as!(
+		PreferenceChange,
+		r#"
+		SELECT
+			id,
+			name,
+			old_preference as "old_preference?: NamePreference",
+			old_stars as "old_stars?: u8",
+			new_preference as "new_preference?: NamePreference",
+			new_stars as "new_stars?: u8",
+			voter,
+			changed_at as "changed_at: NaiveDateTime"
+		FROM preference_history
+		WHERE
+			project_id = $1
+			AND id = $2
+		"#,
+		project_id,
+		id,
+	)
+	.fetch_optional(database_pool)
+	.await
+}
+
+/// Imported meaning, origin and name days, everything is empty if there is no metadata for the name
+#[derive(Clone, Debug, Default)]
+pub struct NameMetadata {
+	pub meaning: Option<String>,
+	pub origin: Option<String>,
+	pub name_days: Vec<NameDay>,
+}
+
+impl NameMetadata {
+	pub fn is_empty(&self) -> bool {
+		self.meaning.is_none() && self.origin.is_none() && self.name_days.is_empty()
+	}
+}
+
+pub async fn read_name_metadata(name: &str, database_pool: &SqlitePool) -> sqlx::Result<NameMetadata> {
+	let metadata = sqlx::query!(
+		r#"
+		SELECT meaning, origin
+		FROM name_metadata
+		WHERE name = $1
+		"#,
+		name,
+	)
+	.fetch_optional(database_pool)
+	.await?;
+
+	let name_days = sqlx::query_as!(
+		NameDay,
+		r#"
+		SELECT
+			calendar as "calendar: Calendar",
+			month as "month: u8",
+			day as "day: u8"
+		FROM name_days
+		WHERE name = $1
+		ORDER BY calendar, month, day
+		"#,
+		name,
+	)
+	.fetch_all(database_pool)
+	.await?;
+
+	Ok(NameMetadata {
+		meaning: metadata.as_ref().and_then(|metadata| metadata.meaning.clone()),
+		origin: metadata.and_then(|metadata| metadata.origin),
+		name_days,
+	})
+}
+
+#[derive(Clone, Debug)]
+pub struct Nickname {
+	pub name: String,
+	/// Derived with [`derive_nicknames`] instead of imported
+	pub derived: bool,
+	/// The nickname is itself a name that was marked as no-go in the project
+	pub no_go: bool,
+}
+
+/// Imported nicknames first, followed by the derived ones
+pub async fn read_nicknames(
+	name: &str,
+	language: Language,
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<Vec<Nickname>> {
+	let imported = sqlx::query_scalar!(
+		r#"
+		SELECT nickname
+		FROM nicknames
+		WHERE name = $1
+		ORDER BY nickname ASC
+		"#,
+		name,
+	)
+	.fetch_all(database_pool)
+	.await?;
+	let derived = derive_nicknames(name, language)
+		.into_iter()
+		.filter(|nickname| !imported.contains(nickname))
+		.collect::<Vec<_>>();
+
+	let mut nicknames = Vec::with_capacity(imported.len() + derived.len());
+	for (nickname, derived) in imported
+		.into_iter()
+		.map(|nickname| (nickname, false))
+		.chain(derived.into_iter().map(|nickname| (nickname, true)))
+	{
+		let no_go = sqlx::query_scalar!(
+			r#"
+			SELECT EXISTS (
+				SELECT 1
+				FROM name_preference
+				WHERE
+					project_id = $1
+					AND name = $2
+					AND preference = 'no_go'
+			) as "no_go!: bool"
+			"#,
+			project_id,
+			nickname,
+		)
+		.fetch_one(database_pool)
+		.await?;
+		nicknames.push(Nickname {
+			name: nickname,
+			derived,
+			no_go,
+		});
+	}
+	Ok(nicknames)
+}
+
+/// The name itself and all of its spelling variants
+pub async fn read_name_group(name: &str, database_pool: &SqlitePool) -> sqlx::Result<Vec<String>> {
+	sqlx::query_scalar!(
+		r#"
+		SELECT $1 as "name!: String"
+		UNION
+		SELECT other.name
+		FROM name_group_members as own
+		JOIN name_group_members as other
+			ON own.group_id = other.group_id
+		WHERE own.name = $1
+		ORDER BY 1
+		"#,
+		name,
+	)
+	.fetch_all(database_pool)
+	.await
+}
+
+#[derive(Clone, Debug)]
+pub struct CombinationWithPreference {
+	pub combination: Combination,
+	pub preference: Option<NamePreference>,
+}
+
+/// All ordered pairs of favorite names of the project with compatible genders, optionally also as hyphenated double
+/// names
+pub async fn read_combinations(
+	include_hyphenated: bool,
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<Vec<CombinationWithPreference>> {
+	let combinations = sqlx::query!(
+		r#"
+		SELECT
+			first.name as first_name,
+			middle.name as middle_name,
+			hyphenated.value as "hyphenated!: bool",
+			combination_preference.preference as "preference?: NamePreference"
+		FROM names as first
+		JOIN name_preference as first_preference
+			ON first_preference.name = first.name
+			AND first_preference.project_id = $2
+		JOIN names as middle
+			ON middle.name != first.name
+			AND (first.gender = middle.gender OR first.gender = 'both' OR middle.gender = 'both')
+		JOIN name_preference as middle_preference
+			ON middle_preference.name = middle.name
+			AND middle_preference.project_id = $2
+		JOIN (SELECT FALSE as value UNION ALL SELECT TRUE) as hyphenated
+			ON $1 OR NOT hyphenated.value
+		LEFT JOIN combination_preference
+			ON combination_preference.project_id = $2
+			AND combination_preference.first_name = first.name
+			AND combination_preference.middle_name = middle.name
+			AND combination_preference.hyphenated = hyphenated.value
+		WHERE
+			first_preference.preference = 'favorite'
+			AND middle_preference.preference = 'favorite'
+		ORDER BY first.name, middle.name, hyphenated.value
+		"#,
+		include_hyphenated,
+		project_id,
+	)
+	.fetch_all(database_pool)
+	.await?;
+
+	Ok(combinations
+		.into_iter()
+		.map(|combination| CombinationWithPreference {
+			combination: Combination {
+				first_name: combination.first_name,
+				middle_name: combination.middle_name,
+				hyphenated: combination.hyphenated,
+			},
+			preference: combination.preference,
+		})
+		.collect())
+}
+
+pub async fn read_combination(
+	combination: &Combination,
+	project_id: ProjectId,
+	database_pool: &SqlitePool,
+) -> sqlx::Result<CombinationWithPreference> {
+	let preference = sqlx::query_scalar!(
+		r#"
+		SELECT preference as "preference: NamePreference"
+		FROM combination_preference
+		WHERE
+			project_id = $1
+			AND first_name = $2
+			AND middle_name = $3
+			AND hyphenated = $4
+		"#,
+		project_id,
+		combination.first_name,
+		combination.middle_name,
+		combination.hyphenated,
+	)
+	.fetch_optional(database_pool)
+	.await?;
+
+	Ok(CombinationWithPreference {
+		combination: combination.clone(),
+		preference,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::csv_parser::NameRecord;
+	use crate::database;
+	use crate::name_metadata::NameMetadataRecord;
+	use sqlx::sqlite::SqlitePoolOptions;
+
+	const PROJECT_ID: ProjectId = 1;
+
+	/// Anna is tagged and has a note, Theo is a rated favorite with metadata
+	async fn example_database() -> SqlitePool {
+		// every connection to an in-memory database gets its own database
+		let database_pool = SqlitePoolOptions::new()
+			.max_connections(1)
+			.idle_timeout(None)
+			.max_lifetime(None)
+			.connect("sqlite::memory:")
+			.await
+			.unwrap();
+		sqlx::migrate!("./migrations").run(&database_pool).await.unwrap();
+
+		for (name, count, gender) in [("Anna", 300, Gender::Female), ("Theo", 50, Gender::Male)] {
+			database::upsert_name(name, gender, &database_pool).await.unwrap();
+			database::replace_phonetic_codes(name, &phonetic_codes(name), &database_pool)
+				.await
+				.unwrap();
+			let record = NameRecord {
+				name: name.to_owned(),
+				count,
+				gender,
+			};
+			database::insert_name_record(&record, "2024.csv", &database_pool)
+				.await
+				.unwrap();
+		}
+		let metadata = NameMetadataRecord {
+			name: "Theo".to_owned(),
+			meaning: Some("Gift of God".to_owned()),
+			origin: Some("Greek".to_owned()),
+			name_days: vec![NameDay {
+				calendar: Calendar::Catholic,
+				month: 3,
+				day: 1,
+			}],
+		};
+		database::replace_name_metadata(&metadata, &database_pool)
+			.await
+			.unwrap();
+		database::insert_name_note("Anna", None, "short and sweet", PROJECT_ID, &database_pool)
+			.await
+			.unwrap();
+		database::tag_names("short", &["Anna".to_owned()], PROJECT_ID, &database_pool)
+			.await
+			.unwrap();
+		let rating = Rating {
+			preference: Some(NamePreference::Favorite),
+			stars: Some(4),
+		};
+		database::set_name_rating("Theo", rating, None, PROJECT_ID, &database_pool)
+			.await
+			.unwrap();
+
+		database_pool
+	}
+
+	async fn search(search: &NameSearch, database_pool: &SqlitePool) -> Vec<String> {
+		read_all_names(
+			Gender::Both,
+			PreferenceSelection::ALL,
+			search,
+			&StructureFilter::default(),
+			false,
+			None,
+			PROJECT_ID,
+			database_pool,
+		)
+		.await
+		.unwrap_or_else(|error| panic!("{search:?} failed: {error}"))
+		.into_iter()
+		.map(|name| name.name)
+		.collect()
+	}
+
+	#[tokio::test]
+	async fn every_predicate_runs_in_sqlite() {
+		let database_pool = example_database().await;
+
+		for (query, expected) in [
+			("", &["Anna", "Theo"][..]),
+			("an", &["Anna"]),
+			("sweet", &["Anna"]),
+			("-sweet", &["Theo"]),
+			("100%", &[]),
+			("starts:th", &["Theo"]),
+			("ends:A", &["Anna"]),
+			("len:4", &["Anna", "Theo"]),
+			("pop:>100", &["Anna"]),
+			("gender:male", &["Theo"]),
+			("pref:favorite", &["Theo"]),
+			("pref:undecided", &["Anna"]),
+			("stars:>=4", &["Theo"]),
+			("stars:0", &["Anna"]),
+			("tag:SHORT", &["Anna"]),
+			("origin:greek", &["Theo"]),
+			("meaning:god", &["Theo"]),
+			("nameday:spring", &["Theo"]),
+			("-nameday:3", &["Anna"]),
+		] {
+			let query = SearchQuery::parse(query).unwrap();
+			assert_eq!(
+				search(&NameSearch::Query(query.clone()), &database_pool).await,
+				expected,
+				"{query:?}"
+			);
+		}
+
+		let name_only = SearchQuery {
+			terms: vec![Term {
+				negated: false,
+				predicate: Predicate::NameContains("sweet".to_owned()),
+			}],
+		};
+		assert!(search(&NameSearch::Query(name_only), &database_pool).await.is_empty());
+	}
+
+	#[tokio::test]
+	async fn sounds_like_and_fuzzy_searches_run_in_sqlite() {
+		let database_pool = example_database().await;
+
+		assert_eq!(
+			search(&NameSearch::SoundsLike("Teo".to_owned()), &database_pool).await,
+			["Theo"]
+		);
+		assert_eq!(
+			search(&NameSearch::Fuzzy("Anne".to_owned()), &database_pool).await,
+			["Anna"]
+		);
+		assert_eq!(
+			search(&NameSearch::Fuzzy("An".to_owned()), &database_pool).await,
+			["Anna"]
+		);
+	}
+}
@@ -9,7 +9,12 @@ use tokio::runtime::Runtime;
 
 const APPLICATION_ID: &str = "de.maxbruckner.baby-name-tournament";
 
+relm4::new_action_group!(WindowActionGroup, "win");
+relm4::new_stateless_action!(UndoAction, WindowActionGroup, "undo");
+relm4::new_stateless_action!(RedoAction, WindowActionGroup, "redo");
+
 mod backend;
+mod combination_list;
 mod database_list;
 mod force_unwrapped_field;
 mod gender_dropdown;
@@ -17,19 +22,29 @@ mod main_view;
 mod name_list;
 mod name_preference;
 mod runtime_thread;
+mod undo_stack;
 
 use crate::database;
-use crate::database::views::NameWithPreference;
+use crate::database::views::{CombinationWithPreference, NameWithPreference};
+use crate::database::{Project, Rating, RatingChange};
+use crate::gui::combination_list::{CombinationList, CombinationListInput, CombinationListOutput};
 use crate::gui::main_view::{MainView, MainViewInput, MainViewOutput};
+use crate::gui::undo_stack::UndoStack;
 use backend::Backend;
+use relm4::actions::{AccelsPlus, ActionName, RelmAction, RelmActionGroup};
 
-pub fn start(runtime: Runtime, database_pool: &SqlitePool) -> anyhow::Result<()> {
+pub fn start(
+	runtime: Runtime,
+	database_pool: &SqlitePool,
+	project: Project,
+	voter: Option<String>,
+) -> anyhow::Result<()> {
 	let runtime_thread = RuntimeThread::start(runtime);
 	let handle = runtime_thread.handle().clone();
 
 	RelmApp::new(APPLICATION_ID)
 		.with_args(vec![])
-		.run::<Application>(Backend::new(database_pool.clone(), handle.clone()));
+		.run::<Application>(Backend::new(database_pool.clone(), handle.clone(), project, voter));
 
 	handle.block_on(database_pool.close());
 
@@ -38,12 +53,26 @@ pub fn start(runtime: Runtime, database_pool: &SqlitePool) -> anyhow::Result<()>
 
 struct Application {
 	main_view_controller: Controller<MainView>,
+	combination_list_controller: Controller<CombinationList>,
+	/// All projects in the order of the project switcher
+	projects: Vec<Project>,
+	undo_stack: UndoStack,
+	toast_overlay: adw::ToastOverlay,
 	backend: Backend,
 }
 
 #[derive(Debug)]
 enum ApplicationMessage {
-	NamePreferenceUpdated(NameWithPreference),
+	NamePreferencesUpdated(Vec<NameWithPreference>),
+	GroupPreferenceUpdated {
+		name: String,
+		rating: Rating,
+	},
+	CombinationRated(CombinationWithPreference),
+	/// Index into the projects of the project switcher
+	SwitchProject(u32),
+	Undo,
+	Redo,
 }
 
 #[relm4::component]
@@ -57,13 +86,32 @@ impl SimpleComponent for Application {
 			set_title: Some("Baby Name Tournament"),
 			set_default_size: (480, 640),
 
-			gtk::Box {
-				set_orientation: Orientation::Vertical,
+			#[local_ref]
+			toast_overlay -> adw::ToastOverlay {
+				#[wrap(Some)]
+				set_child = &gtk::Box {
+					set_orientation: Orientation::Vertical,
+
+					HeaderBar {
+						pack_start = &gtk::DropDown {
+							set_model: Some(&gtk::StringList::new(&project_names)),
+							set_selected: selected_project,
+							set_tooltip_text: Some("Project"),
+							connect_selected_notify[sender] => move |dropdown| {
+								sender.input(ApplicationMessage::SwitchProject(dropdown.selected()));
+							}
+						},
 
-				HeaderBar {},
+						#[wrap(Some)]
+						set_title_widget = &adw::ViewSwitcher {
+							set_stack: Some(&view_stack),
+							set_policy: adw::ViewSwitcherPolicy::Wide,
+						},
+					},
 
-				#[local]
-				main_view -> gtk::Box {},
+					#[local]
+					view_stack -> adw::ViewStack {},
+				},
 			}
 		}
 	}
@@ -73,46 +121,217 @@ impl SimpleComponent for Application {
 			MainView::builder()
 				.launch(backend.clone())
 				.forward(sender.input_sender(), |message| match message {
-					MainViewOutput::NamePreferenceUpdated(name_with_preference) => {
-						ApplicationMessage::NamePreferenceUpdated(name_with_preference)
+					MainViewOutput::NamePreferencesUpdated(names_with_preference) => {
+						ApplicationMessage::NamePreferencesUpdated(names_with_preference)
+					}
+					MainViewOutput::GroupPreferenceUpdated { name, rating } => {
+						ApplicationMessage::GroupPreferenceUpdated { name, rating }
 					}
 				});
 		let main_view = main_view_controller.widget().clone();
 
+		let combination_list_controller = CombinationList::builder().launch(backend.clone()).forward(
+			sender.input_sender(),
+			|message| match message {
+				CombinationListOutput::PreferenceUpdated(combination_with_preference) => {
+					ApplicationMessage::CombinationRated(combination_with_preference)
+				}
+			},
+		);
+		let combination_list = combination_list_controller.widget().clone();
+
+		let view_stack = adw::ViewStack::new();
+		view_stack
+			.add_titled(&main_view, Some("names"), "Names")
+			.set_icon_name(Some("system-users-symbolic"));
+		view_stack
+			.add_titled(&combination_list, Some("combinations"), "Combinations")
+			.set_icon_name(Some("view-list-symbolic"));
+
+		let projects = backend
+			.block_on_future(database::read_projects(backend.database_pool()))
+			.expect("Failed to read projects");
+		let project_names = projects.iter().map(|project| project.name.as_str()).collect::<Vec<_>>();
+		let current_project_id = backend.project().id;
+		let selected_project = projects
+			.iter()
+			.position(|project| project.id == current_project_id)
+			.and_then(|index| u32::try_from(index).ok())
+			.unwrap_or_default();
+
 		let model = Self {
 			main_view_controller,
+			combination_list_controller,
+			projects: projects.clone(),
+			undo_stack: UndoStack::default(),
+			toast_overlay: adw::ToastOverlay::new(),
 			backend,
 		};
+		let toast_overlay = &model.toast_overlay;
 
 		let widgets = view_output!();
 
+		let mut actions = RelmActionGroup::<WindowActionGroup>::new();
+		let undo_sender = sender.clone();
+		actions.add_action(RelmAction::<UndoAction>::new_stateless(move |_| {
+			undo_sender.input(ApplicationMessage::Undo);
+		}));
+		let redo_sender = sender.clone();
+		actions.add_action(RelmAction::<RedoAction>::new_stateless(move |_| {
+			redo_sender.input(ApplicationMessage::Redo);
+		}));
+		actions.register_for_widget(&root);
+		let application = relm4::main_application();
+		application.set_accelerators_for_action::<UndoAction>(&["<Control>z"]);
+		application.set_accelerators_for_action::<RedoAction>(&["<Control><Shift>z"]);
+
 		ComponentParts { model, widgets }
 	}
 
 	fn update(&mut self, message: Self::Input, _sender: ComponentSender<Self>) {
 		use ApplicationMessage::*;
 		match message {
-			NamePreferenceUpdated(NameWithPreference { name, preference, .. }) => {
+			NamePreferencesUpdated(names_with_preference) => {
+				let ratings = names_with_preference
+					.into_iter()
+					.map(|name_with_preference| {
+						let rating = name_with_preference.rating();
+						(name_with_preference.name, rating)
+					})
+					.collect::<Vec<_>>();
+				let changes = self.write_ratings(&ratings);
+				self.record_changes(changes);
+			}
+			GroupPreferenceUpdated { name, rating } => {
+				let changes = self
+					.backend
+					.block_on_future(database::set_group_preference(
+						&name,
+						rating,
+						self.backend.voter(),
+						self.backend.project().id,
+						self.backend.database_pool(),
+					))
+					.expect("Failed to update preference of name group");
+				self.refresh_names(&changes);
+				self.record_changes(changes);
+			}
+			Undo => {
+				let Some(ratings) = self.undo_stack.undo() else {
+					return;
+				};
+				let changes = self.write_ratings(&ratings);
+				self.show_toast::<RedoAction>(&format!("Undid {}", describe_changes(&changes)), "Redo");
+			}
+			Redo => {
+				let Some(ratings) = self.undo_stack.redo() else {
+					return;
+				};
+				let changes = self.write_ratings(&ratings);
+				self.show_toast::<UndoAction>(&format!("Redid {}", describe_changes(&changes)), "Undo");
+			}
+			CombinationRated(CombinationWithPreference {
+				combination,
+				preference,
+			}) => {
 				match preference {
-					Some(preference) => {
-						self.backend
-							.block_on_future(database::upsert_name_preference(
-								&name,
-								preference,
-								self.backend.database_pool(),
-							))
-							.expect("Failed to update name preference");
-					}
+					Some(preference) => self
+						.backend
+						.block_on_future(database::upsert_combination_preference(
+							&combination,
+							preference,
+							self.backend.project().id,
+							self.backend.database_pool(),
+						))
+						.expect("Failed to update combination preference"),
 					None => self
 						.backend
-						.block_on_future(database::delete_name_preference(&name, self.backend.database_pool()))
-						.expect("Failed to delete name preference"),
+						.block_on_future(database::delete_combination_preference(
+							&combination,
+							self.backend.project().id,
+							self.backend.database_pool(),
+						))
+						.expect("Failed to delete combination preference"),
+				}
+				let _ = self
+					.combination_list_controller
+					.sender()
+					.send(CombinationListInput::RefreshRow { combination });
+			}
+			SwitchProject(index) => {
+				let Some(project) = usize::try_from(index).ok().and_then(|index| self.projects.get(index)) else {
+					return;
+				};
+				if project.id == self.backend.project().id {
+					return;
 				}
+
+				self.backend.set_project(project.clone());
+				// the changes were made in the other project
+				self.undo_stack.clear();
+				let _ = self.main_view_controller.sender().send(MainViewInput::Refresh);
 				let _ = self
-					.main_view_controller
+					.combination_list_controller
 					.sender()
-					.send(MainViewInput::RefreshRow { name });
+					.send(CombinationListInput::Refresh);
 			}
 		}
 	}
 }
+
+impl Application {
+	/// Writes all ratings in a single transaction and refreshes the affected names
+	fn write_ratings(&self, ratings: &[(String, Rating)]) -> Vec<RatingChange> {
+		let changes = self
+			.backend
+			.block_on_future(database::set_name_ratings(
+				ratings,
+				self.backend.voter(),
+				self.backend.project().id,
+				self.backend.database_pool(),
+			))
+			.expect("Failed to update name preferences");
+		self.refresh_names(&changes);
+		changes
+	}
+
+	fn refresh_names(&self, changes: &[RatingChange]) {
+		for RatingChange { name, .. } in changes {
+			let _ = self
+				.main_view_controller
+				.sender()
+				.send(MainViewInput::RefreshRow { name: name.clone() });
+		}
+		let _ = self
+			.combination_list_controller
+			.sender()
+			.send(CombinationListInput::Refresh);
+	}
+
+	/// Makes the changes undoable, both with the shortcut and the button of the toast
+	fn record_changes(&mut self, changes: Vec<RatingChange>) {
+		if changes.is_empty() {
+			return;
+		}
+
+		self.show_toast::<UndoAction>(&describe_changes(&changes), "Undo");
+		self.undo_stack.push(changes);
+	}
+
+	/// The button of the toast triggers the action
+	fn show_toast<Action: ActionName>(&self, title: &str, button_label: &str) {
+		let toast = adw::Toast::builder()
+			.title(title)
+			.button_label(button_label)
+			.action_name(Action::action_name())
+			.build();
+		self.toast_overlay.add_toast(toast);
+	}
+}
+
+fn describe_changes(changes: &[RatingChange]) -> String {
+	match changes {
+		[RatingChange { name, after, .. }] => format!("{name}: {after}"),
+		changes => format!("{} names changed", changes.len()),
+	}
+}
@@ -1,9 +1,9 @@
 use crate::csv_parser::Gender;
 use crate::database;
-use crate::database::Name;
+use crate::database::{Name, Project};
 use futures_util::TryStreamExt;
 use sqlx::SqlitePool;
-use std::cell::OnceCell;
+use std::cell::{OnceCell, RefCell};
 use std::future::Future;
 use std::ops::Deref;
 use std::rc::Rc;
@@ -17,14 +17,25 @@ pub struct Backend {
 struct BackendInner {
 	database_pool: SqlitePool,
 	runtime_handle: runtime::Handle,
+	/// The project whose preferences are shown and changed
+	project: RefCell<Project>,
+	/// Who is using the GUI, recorded with notes and preference changes
+	voter: Option<String>,
 }
 
 impl Backend {
-	pub fn new(database_pool: SqlitePool, runtime_handle: runtime::Handle) -> Self {
+	pub fn new(
+		database_pool: SqlitePool,
+		runtime_handle: runtime::Handle,
+		project: Project,
+		voter: Option<String>,
+	) -> Self {
 		Self {
 			inner: Rc::new(BackendInner {
 				database_pool,
 				runtime_handle,
+				project: project.into(),
+				voter,
 			})
 			.into(),
 		}
@@ -49,6 +60,18 @@ impl Backend {
 		&self.inner().runtime_handle
 	}
 
+	pub fn project(&self) -> Project {
+		self.inner().project.borrow().clone()
+	}
+
+	pub fn set_project(&self, project: Project) {
+		*self.inner().project.borrow_mut() = project;
+	}
+
+	pub fn voter(&self) -> Option<&str> {
+		self.inner().voter.as_deref()
+	}
+
 	pub async fn list_all_names(&self) -> Vec<Name> {
 		let database_pool = self.database_pool().clone();
 		self.run_future(async move { database::list_all(Gender::Both, &database_pool).try_collect().await })
@@ -0,0 +1,212 @@
+use crate::combinations::{Combination, CombinationAnalysis};
+use crate::database;
+use crate::database::views::CombinationWithPreference;
+use crate::gui::backend::Backend;
+use crate::gui::combination_list::combination_list_row::{
+	CombinationListRow, CombinationListRowInput, CombinationListRowOutput,
+};
+use crate::gui::database_list::{DatabaseListManager, DatabaseListModel, DatabaseView, Model};
+use crate::name_structure::Language;
+use adw::glib;
+use glib::BoxedAnyObject;
+use gtk::ffi::GtkListItem;
+use gtk::{Orientation, PolicyType, SignalListItemFactory, prelude::*};
+use relm4::{Component, ComponentController, ComponentParts, ComponentSender, Controller, Sender, SimpleComponent};
+use relm4::{adw, gtk};
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+mod combination_list_row;
+
+/// First + middle name combinations of all favorite names
+pub struct CombinationList {
+	list_manager: DatabaseListManager<CombinationListView>,
+	filter: CombinationListViewFilter,
+}
+
+#[derive(Debug)]
+pub enum CombinationListInput {
+	SetIncludeHyphenated(bool),
+	PreferenceUpdated(CombinationWithPreference),
+	/// The favorites or the project changed, so the combinations have to be generated again
+	Refresh,
+	RefreshRow {
+		combination: Combination,
+	},
+}
+
+#[derive(Debug)]
+pub enum CombinationListOutput {
+	PreferenceUpdated(CombinationWithPreference),
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for CombinationList {
+	type Input = CombinationListInput;
+	type Output = CombinationListOutput;
+	type Init = Backend;
+
+	view! {
+		gtk::Box {
+			set_orientation: Orientation::Vertical,
+
+			gtk::CheckButton {
+				set_label: Some("Include hyphenated double names"),
+				connect_toggled[sender] => move |button| {
+					sender.input(CombinationListInput::SetIncludeHyphenated(button.is_active()));
+				}
+			},
+
+			gtk::ScrolledWindow {
+				set_hscrollbar_policy: PolicyType::Never,
+				set_vexpand: true,
+
+				#[local_ref]
+				combination_list -> gtk::ListView {
+					set_show_separators: true,
+				}
+			},
+		}
+	}
+
+	fn init(backend: Self::Init, root: Self::Root, sender: ComponentSender<Self>) -> ComponentParts<Self> {
+		let filter = CombinationListViewFilter::default();
+		let combination_factory = combination_row_factory(sender.input_sender(), &backend);
+
+		let list_manager = DatabaseListManager::new(filter.clone(), CombinationListView, backend)
+			.expect("Failed to initialize combination list manager");
+		let list_model = DatabaseListModel::new(list_manager.clone());
+		let selection_model = gtk::NoSelection::new(Some(list_model));
+		let combination_list = gtk::ListView::new(Some(selection_model), Some(combination_factory));
+
+		let model = Self { list_manager, filter };
+		let widgets = view_output!();
+		ComponentParts { model, widgets }
+	}
+
+	fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>) {
+		use CombinationListInput::*;
+		match message {
+			SetIncludeHyphenated(include_hyphenated) => {
+				self.filter.include_hyphenated = include_hyphenated;
+				self.list_manager
+					.update_filter(self.filter.clone())
+					.expect("Failed to update list manager");
+			}
+			PreferenceUpdated(combination_with_preference) => {
+				let _ = sender.output(CombinationListOutput::PreferenceUpdated(combination_with_preference));
+			}
+			Refresh => self
+				.list_manager
+				.notify_changed()
+				.expect("Failed to refresh combinations"),
+			RefreshRow { combination } => self
+				.list_manager
+				.notify_updated(&combination)
+				.expect("Failed to update single list entry"),
+		}
+	}
+}
+
+fn combination_row_factory(input_sender: &Sender<CombinationListInput>, backend: &Backend) -> SignalListItemFactory {
+	let combination_factory = SignalListItemFactory::new();
+
+	// FIXME: Find a better way than the pointer of the root widget to identify which component is hooked up to which GtkListItem
+	let controllers = Rc::new(RefCell::new(
+		HashMap::<*mut GtkListItem, Controller<CombinationListRow>>::new(),
+	));
+
+	combination_factory.connect_setup({
+		let input_sender = input_sender.clone();
+		let controllers = controllers.clone();
+		let backend = backend.clone();
+		move |_, list_item| {
+			let controller = CombinationListRow::builder()
+				.launch(backend.clone())
+				.forward(&input_sender, |output| match output {
+					CombinationListRowOutput::PreferenceSet(combination_with_preference) => {
+						CombinationListInput::PreferenceUpdated(combination_with_preference)
+					}
+				});
+			list_item.set_child(Some(controller.widget()));
+			controllers.borrow_mut().insert(list_item.as_ptr(), controller);
+		}
+	});
+	combination_factory.connect_bind({
+		let controllers = controllers.clone();
+		move |_, list_item| {
+			let controllers = controllers.borrow();
+			let controller = controllers
+				.get(&list_item.as_ptr())
+				.expect("No controller for this list item");
+
+			let item = list_item
+				.item()
+				.expect("Missing item")
+				.downcast::<BoxedAnyObject>()
+				.expect("Incorrect Type");
+			let combination_with_preference = item.borrow::<CombinationWithPreference>();
+
+			let _ = controller.sender().send(CombinationListRowInput::SetCombination(
+				combination_with_preference.clone(),
+			));
+		}
+	});
+
+	combination_factory.connect_teardown(move |_, list_item| {
+		controllers.borrow_mut().remove(&list_item.as_ptr());
+	});
+
+	combination_factory
+}
+
+#[derive(Clone, Default)]
+pub struct CombinationListView;
+
+#[derive(Clone, Debug, Default)]
+pub struct CombinationListViewFilter {
+	pub include_hyphenated: bool,
+}
+
+impl DatabaseView for CombinationListView {
+	type Model = CombinationWithPreference;
+	type Filter = CombinationListViewFilter;
+
+	fn read_all(
+		&self,
+		backend: &Backend,
+		CombinationListViewFilter { include_hyphenated }: &Self::Filter,
+	) -> anyhow::Result<Vec<Self::Model>> {
+		let mut combinations = backend.block_on_future(database::views::read_combinations(
+			*include_hyphenated,
+			backend.project().id,
+			backend.database_pool(),
+		))?;
+
+		// best fit first, also without a surname since the first and middle name have to go well together as well
+		let surname = backend.project().surname;
+		combinations.sort_by_cached_key(|CombinationWithPreference { combination, .. }| {
+			Reverse(CombinationAnalysis::new(combination, surname.as_deref(), Language::default()).score())
+		});
+
+		Ok(combinations)
+	}
+
+	fn read_by_key(&self, backend: &Backend, key: &<Self::Model as Model>::Key) -> anyhow::Result<Self::Model> {
+		Ok(backend.block_on_future(database::views::read_combination(
+			key,
+			backend.project().id,
+			backend.database_pool(),
+		))?)
+	}
+}
+
+impl Model for CombinationWithPreference {
+	type Key = Combination;
+
+	fn unique_key(&self) -> &Self::Key {
+		&self.combination
+	}
+}
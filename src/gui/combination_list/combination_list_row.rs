@@ -0,0 +1,136 @@
+use crate::combinations::{Combination, CombinationAnalysis};
+use crate::database::Rating;
+use crate::database::views::CombinationWithPreference;
+use crate::gui::backend::Backend;
+use crate::gui::name_preference::{NamePreferenceInput, NamePreferenceView};
+use crate::name_structure::Language;
+use gtk::prelude::*;
+use relm4::{Component, ComponentController, ComponentParts, ComponentSender, Controller, SimpleComponent, gtk};
+
+pub struct CombinationListRow {
+	combination: CombinationWithPreference,
+	preference_controller: Controller<NamePreferenceView>,
+	/// Family surname of the current project that the combination is checked against, if one was configured
+	surname: Option<String>,
+	backend: Backend,
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for CombinationListRow {
+	type Input = CombinationListRowInput;
+	type Output = CombinationListRowOutput;
+	type Init = Backend;
+
+	view! {
+		gtk::Box {
+			set_homogeneous: true,
+
+			gtk::Label {
+				set_use_markup: true,
+				#[watch]
+				set_label: &format!(r"<big><b>{}</b></big>", model.combination.combination),
+			},
+
+			gtk::Label {
+				#[watch]
+				set_label: &format!("Fit: {}", model.analysis().score()),
+				#[watch]
+				set_tooltip_text: Some(&{
+					let issues = model.analysis().issues();
+					if issues.is_empty() {
+						"Goes well together".to_owned()
+					} else {
+						issues.join("\n")
+					}
+				}),
+			},
+
+			#[local]
+			preference_widget -> gtk::Box {},
+
+			gtk::Box {
+				gtk::Button {
+					set_icon_name: "edit-undo-symbolic",
+					set_vexpand: false,
+					set_hexpand: false,
+
+					connect_clicked[sender] => move |_| {
+						sender.input(CombinationListRowInput::UpdatePreference(Rating::default()));
+					}
+				},
+			}
+		}
+	}
+
+	fn init(backend: Self::Init, root: Self::Root, sender: ComponentSender<Self>) -> ComponentParts<Self> {
+		let preference_controller = NamePreferenceView::builder()
+			// combinations are only marked as favorites, not rated
+			.launch(("Preference", 1))
+			.forward(sender.input_sender(), CombinationListRowInput::UpdatePreference);
+		let preference_widget = preference_controller.widget().clone();
+
+		let surname = backend.project().surname;
+
+		let model = Self {
+			combination: CombinationWithPreference {
+				combination: Combination {
+					first_name: "{none}".to_owned(),
+					middle_name: "{none}".to_owned(),
+					hyphenated: false,
+				},
+				preference: None,
+			},
+			preference_controller,
+			surname,
+			backend,
+		};
+
+		let widgets = view_output!();
+
+		ComponentParts { widgets, model }
+	}
+
+	fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>) {
+		use CombinationListRowInput::*;
+		match message {
+			SetCombination(combination) => {
+				// the project might have been switched since the row was last used
+				self.surname = self.backend.project().surname;
+				self.combination = combination;
+			}
+			UpdatePreference(Rating { preference, .. }) => {
+				self.combination.preference = preference;
+				let _ = sender.output(CombinationListRowOutput::PreferenceSet(self.combination.clone()));
+			}
+		}
+
+		let _ = self
+			.preference_controller
+			.sender()
+			.send(NamePreferenceInput::SetRating(Rating {
+				preference: self.combination.preference,
+				stars: None,
+			}));
+	}
+}
+
+impl CombinationListRow {
+	fn analysis(&self) -> CombinationAnalysis {
+		CombinationAnalysis::new(
+			&self.combination.combination,
+			self.surname.as_deref(),
+			Language::default(),
+		)
+	}
+}
+
+#[derive(Debug)]
+pub enum CombinationListRowInput {
+	SetCombination(CombinationWithPreference),
+	UpdatePreference(Rating),
+}
+
+#[derive(Debug)]
+pub enum CombinationListRowOutput {
+	PreferenceSet(CombinationWithPreference),
+}
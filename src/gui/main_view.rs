@@ -1,11 +1,17 @@
 use crate::csv_parser::Gender;
-use crate::database::views::NameWithPreference;
+use crate::database;
+use crate::database::Rating;
+use crate::database::views::{NameSearch, NameWithPreference, PreferenceSelection};
 use crate::gui::backend::Backend;
 use crate::gui::gender_dropdown::GenderDropdown;
 use crate::gui::main_view::preference_filter::{PreferenceFilter, PreferenceFilterComponent, PreferenceFilterOutput};
-use crate::gui::name_list::{NameList, NameListInput, NameListOutput, NameListView, NameListViewFilter};
+use crate::gui::name_list::{NameList, NameListInput, NameListOutput, NameListView, NameListViewFilter, SortOrder};
+use crate::search_query::SearchQuery;
 use gtk::{Orientation, prelude::*};
-use relm4::{Component, ComponentController, ComponentParts, ComponentSender, Controller, SimpleComponent, gtk};
+use relm4::{
+	Component, ComponentController, ComponentParts, ComponentSender, Controller, RelmWidgetExt, SimpleComponent, gtk,
+};
+use strum::VariantNames;
 
 mod preference_filter;
 
@@ -14,20 +20,33 @@ pub struct MainView {
 	_gender_filter_controller: Controller<GenderDropdown>,
 	_name_preference_controller: Controller<PreferenceFilterComponent>,
 	filter: NameListViewFilter,
+	/// "any tag" followed by the tags of the project
+	tags: gtk::StringList,
+	search_term: String,
+	search_mode: SearchMode,
+	search_error: Option<String>,
+	backend: Backend,
 }
 
 #[derive(Debug)]
 pub enum MainViewInput {
 	GenderSelected(Gender),
-	NamePreferenceUpdated(NameWithPreference),
+	NamePreferencesUpdated(Vec<NameWithPreference>),
+	GroupPreferenceUpdated { name: String, rating: Rating },
 	UpdateNamePreferenceFilter(PreferenceFilter),
 	UpdateSearchTerm(String),
+	SetSearchMode(SearchMode),
+	SetSortOrder(SortOrder),
+	SetExcludeSimilarToSiblings(bool),
+	SetTag(Option<String>),
+	Refresh,
 	RefreshRow { name: String },
 }
 
 #[derive(Debug)]
 pub enum MainViewOutput {
-	NamePreferenceUpdated(NameWithPreference),
+	NamePreferencesUpdated(Vec<NameWithPreference>),
+	GroupPreferenceUpdated { name: String, rating: Rating },
 }
 
 #[relm4::component(pub)]
@@ -42,19 +61,78 @@ impl SimpleComponent for MainView {
 
 			gtk::Box {
 				set_orientation: Orientation::Horizontal,
-				set_homogeneous: true,
 
 				gtk::SearchEntry {
-					set_placeholder_text: Some("Search ..."),
+					set_hexpand: true,
+					#[watch]
+					set_placeholder_text: Some(match model.search_mode {
+						SearchMode::Query => "Search ... (e.g. len:<6 starts:a -pref:nogo)",
+						SearchMode::Fuzzy => "Search, allowing typos ...",
+						SearchMode::SoundsLike => "Sounds like ...",
+					}),
+					#[watch]
+					set_class_active: ("error", model.search_error.is_some()),
 					connect_search_changed[sender] => move |search_field| {
 						sender.input(MainViewInput::UpdateSearchTerm(search_field.text().as_str().to_owned()));
 					}
 				},
+
+				gtk::DropDown {
+					set_model: Some(&gtk::StringList::new(SearchMode::VARIANTS)),
+					connect_selected_item_notify[sender] => move |dropdown| {
+						let search_mode = dropdown
+							.selected_item()
+							.and_downcast::<gtk::StringObject>()
+							.and_then(|item| item.string().parse().ok())
+							.unwrap_or_default();
+						sender.input(MainViewInput::SetSearchMode(search_mode));
+					}
+				},
+
+				gtk::DropDown {
+					set_model: Some(&gtk::StringList::new(SortOrder::VARIANTS)),
+					set_tooltip_text: Some("Sort order"),
+					connect_selected_item_notify[sender] => move |dropdown| {
+						let sort_order = dropdown
+							.selected_item()
+							.and_downcast::<gtk::StringObject>()
+							.and_then(|item| item.string().parse().ok())
+							.unwrap_or_default();
+						sender.input(MainViewInput::SetSortOrder(sort_order));
+					}
+				},
+			},
+
+			gtk::Label {
+				add_css_class: "error",
+				#[watch]
+				set_visible: model.search_error.is_some(),
+				#[watch]
+				set_label: model.search_error.as_deref().unwrap_or_default(),
 			},
 
 			#[local]
 			gender_dropdown -> gtk::DropDown {},
 
+			gtk::CheckButton {
+				set_label: Some("Exclude names too similar to siblings"),
+				connect_toggled[sender] => move |button| {
+					sender.input(MainViewInput::SetExcludeSimilarToSiblings(button.is_active()));
+				}
+			},
+
+			gtk::DropDown {
+				set_model: Some(&model.tags),
+				set_tooltip_text: Some("Tag"),
+				connect_selected_notify[sender] => move |dropdown| {
+					let tag = (dropdown.selected() > 0)
+						.then(|| dropdown.selected_item().and_downcast::<gtk::StringObject>())
+						.flatten()
+						.map(|item| item.string().to_string());
+					sender.input(MainViewInput::SetTag(tag));
+				}
+			},
+
 			#[local]
 			name_preference_view -> gtk::Box {},
 
@@ -65,10 +143,13 @@ impl SimpleComponent for MainView {
 
 	fn init(backend: Self::Init, root: Self::Root, sender: ComponentSender<Self>) -> ComponentParts<Self> {
 		let name_list_controller = NameList::builder()
-			.launch((NameListViewFilter::default(), backend))
+			.launch((NameListViewFilter::default(), backend.clone()))
 			.forward(sender.input_sender(), |output| match output {
-				NameListOutput::NamePreferenceUpdated(name_with_preferences) => {
-					MainViewInput::NamePreferenceUpdated(name_with_preferences)
+				NameListOutput::NamePreferencesUpdated(names_with_preferences) => {
+					MainViewInput::NamePreferencesUpdated(names_with_preferences)
+				}
+				NameListOutput::GroupPreferenceUpdated { name, rating } => {
+					MainViewInput::GroupPreferenceUpdated { name, rating }
 				}
 			});
 		let name_list = name_list_controller.widget().clone();
@@ -81,9 +162,10 @@ impl SimpleComponent for MainView {
 		let filter = NameListViewFilter::default();
 		let name_preference_controller = PreferenceFilterComponent::builder()
 			.launch(PreferenceFilter {
-				show_favorite: filter.show_favorite,
-				show_nogo: filter.show_nogo,
-				show_undecided: filter.show_undecided,
+				show_favorite: filter.preferences.favorite,
+				show_nogo: filter.preferences.nogo,
+				show_undecided: filter.preferences.undecided,
+				only_due_for_review: filter.preferences.only_due_for_review,
 			})
 			.forward(sender.input_sender(), |message| match message {
 				PreferenceFilterOutput::UpdateFilter(filter) => MainViewInput::UpdateNamePreferenceFilter(filter),
@@ -95,7 +177,13 @@ impl SimpleComponent for MainView {
 			_gender_filter_controller: gender_dropdown_controller,
 			_name_preference_controller: name_preference_controller,
 			filter,
+			tags: gtk::StringList::new(&[ANY_TAG]),
+			search_term: String::new(),
+			search_mode: SearchMode::default(),
+			search_error: None,
+			backend,
 		};
+		model.reload_tags();
 
 		let widgets = view_output!();
 
@@ -112,17 +200,24 @@ impl SimpleComponent for MainView {
 					.sender()
 					.send(NameListInput::UpdateFilter(self.filter.clone()));
 			}
-			NamePreferenceUpdated(name_with_preferences) => {
-				let _ = sender.output(MainViewOutput::NamePreferenceUpdated(name_with_preferences));
+			NamePreferencesUpdated(names_with_preferences) => {
+				let _ = sender.output(MainViewOutput::NamePreferencesUpdated(names_with_preferences));
+			}
+			GroupPreferenceUpdated { name, rating } => {
+				let _ = sender.output(MainViewOutput::GroupPreferenceUpdated { name, rating });
 			}
 			UpdateNamePreferenceFilter(PreferenceFilter {
 				show_favorite,
 				show_nogo,
 				show_undecided,
+				only_due_for_review,
 			}) => {
-				self.filter.show_favorite = show_favorite;
-				self.filter.show_nogo = show_nogo;
-				self.filter.show_undecided = show_undecided;
+				self.filter.preferences = PreferenceSelection {
+					favorite: show_favorite,
+					nogo: show_nogo,
+					undecided: show_undecided,
+					only_due_for_review,
+				};
 
 				let _ = self
 					.name_list_controller
@@ -130,17 +225,39 @@ impl SimpleComponent for MainView {
 					.send(NameListInput::UpdateFilter(self.filter.clone()));
 			}
 			UpdateSearchTerm(search_term) => {
-				self.filter.name_contains = if search_term.trim().is_empty() {
-					None
-				} else {
-					Some(search_term)
-				};
-
+				self.search_term = search_term;
+				self.update_search();
+			}
+			SetSearchMode(search_mode) => {
+				self.search_mode = search_mode;
+				self.update_search();
+			}
+			SetSortOrder(sort_order) => {
+				self.filter.sort_order = sort_order;
+				let _ = self
+					.name_list_controller
+					.sender()
+					.send(NameListInput::UpdateFilter(self.filter.clone()));
+			}
+			SetTag(tag) => {
+				self.filter.tag = tag;
 				let _ = self
 					.name_list_controller
 					.sender()
 					.send(NameListInput::UpdateFilter(self.filter.clone()));
 			}
+			SetExcludeSimilarToSiblings(exclude_similar_to_siblings) => {
+				self.filter.exclude_similar_to_siblings = exclude_similar_to_siblings;
+				let _ = self
+					.name_list_controller
+					.sender()
+					.send(NameListInput::UpdateFilter(self.filter.clone()));
+			}
+			Refresh => {
+				// every project has its own tags
+				self.reload_tags();
+				let _ = self.name_list_controller.sender().send(NameListInput::Refresh);
+			}
 			RefreshRow { name } => {
 				let _ = self
 					.name_list_controller
@@ -150,3 +267,53 @@ impl SimpleComponent for MainView {
 		}
 	}
 }
+
+#[derive(Clone, Copy, Debug, Default, strum::EnumString, strum::VariantNames)]
+pub enum SearchMode {
+	#[default]
+	#[strum(serialize = "query")]
+	Query,
+	#[strum(serialize = "fuzzy")]
+	Fuzzy,
+	#[strum(serialize = "sounds like")]
+	SoundsLike,
+}
+
+const ANY_TAG: &str = "any tag";
+
+impl MainView {
+	fn reload_tags(&self) {
+		let tags = self
+			.backend
+			.block_on_future(database::read_tags(
+				self.backend.project().id,
+				self.backend.database_pool(),
+			))
+			.expect("Failed to read tags");
+		let tags = tags.iter().map(String::as_str).collect::<Vec<_>>();
+		self.tags.splice(1, self.tags.n_items() - 1, &tags);
+	}
+
+	fn update_search(&mut self) {
+		let search_term = self.search_term.trim();
+		let search = match self.search_mode {
+			SearchMode::SoundsLike if !search_term.is_empty() => NameSearch::SoundsLike(search_term.to_owned()),
+			SearchMode::Fuzzy if !search_term.is_empty() => NameSearch::Fuzzy(search_term.to_owned()),
+			_ => match SearchQuery::parse(search_term) {
+				Ok(query) => NameSearch::Query(query),
+				Err(error) => {
+					// keep showing the results of the last valid query while typing
+					self.search_error = Some(error.to_string());
+					return;
+				}
+			},
+		};
+		self.search_error = None;
+		self.filter.search = search;
+
+		let _ = self
+			.name_list_controller
+			.sender()
+			.send(NameListInput::UpdateFilter(self.filter.clone()));
+	}
+}
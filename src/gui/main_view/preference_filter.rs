@@ -1,18 +1,21 @@
 use gtk::{Align, Orientation, prelude::*};
 use relm4::{ComponentParts, ComponentSender, SimpleComponent, gtk};
 
-#[expect(clippy::struct_field_names)]
 pub struct PreferenceFilterComponent {
 	show_favorite_checkbox: gtk::CheckButton,
 	show_nogo_checkbox: gtk::CheckButton,
 	show_undecided_checkbox: gtk::CheckButton,
+	only_due_for_review_button: gtk::ToggleButton,
 }
 
 #[derive(Debug, Copy, Clone)]
+#[expect(clippy::struct_excessive_bools)]
 pub struct PreferenceFilter {
 	pub show_favorite: bool,
 	pub show_nogo: bool,
 	pub show_undecided: bool,
+	/// Only show the favorites that are due for a re-review
+	pub only_due_for_review: bool,
 }
 
 #[derive(Debug)]
@@ -67,6 +70,16 @@ impl SimpleComponent for PreferenceFilterComponent {
 					sender.input(PreferenceFilterInput::UpdateFilter);
 				}
 			},
+
+			#[local]
+			only_due_for_review_button -> gtk::ToggleButton {
+				set_icon_name: "alarm-symbolic",
+				set_tooltip_text: Some("Only favorites due for a re-review"),
+				set_active: preference_filter.only_due_for_review,
+				connect_toggled[sender] => move |_| {
+					sender.input(PreferenceFilterInput::UpdateFilter);
+				}
+			},
 		}
 	}
 
@@ -74,10 +87,12 @@ impl SimpleComponent for PreferenceFilterComponent {
 		let show_favorite_checkbox = gtk::CheckButton::new();
 		let show_nogo_checkbox = gtk::CheckButton::new();
 		let show_undecided_checkbox = gtk::CheckButton::new();
+		let only_due_for_review_button = gtk::ToggleButton::new();
 		let model = Self {
 			show_favorite_checkbox: show_favorite_checkbox.clone(),
 			show_nogo_checkbox: show_nogo_checkbox.clone(),
 			show_undecided_checkbox: show_undecided_checkbox.clone(),
+			only_due_for_review_button: only_due_for_review_button.clone(),
 		};
 
 		let widgets = view_output!();
@@ -93,6 +108,7 @@ impl SimpleComponent for PreferenceFilterComponent {
 					show_favorite: self.show_favorite_checkbox.is_active(),
 					show_nogo: self.show_nogo_checkbox.is_active(),
 					show_undecided: self.show_undecided_checkbox.is_active(),
+					only_due_for_review: self.only_due_for_review_button.is_active(),
 				}));
 			}
 		}
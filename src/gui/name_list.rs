@@ -1,18 +1,21 @@
 use crate::csv_parser::Gender;
 use crate::database;
-use crate::database::Name;
-use crate::database::views::NameWithPreference;
+use crate::database::views::{NameSearch, NameWithPreference, PreferenceSelection};
+use crate::database::{NamePreference, Rating};
 use crate::gui::backend::Backend;
 use crate::gui::database_list::{DatabaseListManager, DatabaseListModel, DatabaseView, Model};
 use crate::gui::name_list::name_list_row::{NameListRow, NameListRowInit, NameListRowInput, NameListRowOutput};
+use crate::name_structure::{Language, StructureFilter};
+use crate::surname::SurnameCompatibility;
 use adw::glib;
 use glib::BoxedAnyObject;
 use gtk::ffi::GtkListItem;
 use gtk::{Orientation, PolicyType, SignalListItemFactory, prelude::*};
-use relm4::{Component, ComponentParts, ComponentSender, SimpleComponent};
+use relm4::{Component, ComponentParts, ComponentSender, Sender, SimpleComponent};
 use relm4::{ComponentController, Controller};
 use relm4::{adw, gtk};
 use std::cell::RefCell;
+use std::cmp::Reverse;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::rc::Rc;
@@ -75,70 +78,24 @@ where
 		sender: ComponentSender<Self>,
 	) -> ComponentParts<Self> {
 		let selected_names_row_controller = NameListRow::builder()
-			.launch(NameListRowInit {
-				name: Name {
-					name: "-".to_owned(),
-					gender: Gender::Both,
+			.launch((
+				NameListRowInit {
+					name: placeholder_name("-"),
+					is_selection_summary: true,
 				},
-				preference: None,
-			})
+				backend.clone(),
+			))
 			.forward(sender.input_sender(), |message| match message {
 				NameListRowOutput::NamePreferenceSet(name_with_preferences) => {
 					NameListInput::MultiselectionPreferenceUpdated(name_with_preferences)
 				}
+				NameListRowOutput::GroupPreferenceSet { name, rating } => {
+					NameListInput::GroupPreferenceUpdated { name, rating }
+				}
 			});
 		let selected_names_row = selected_names_row_controller.widget().clone();
 
-		let name_factory = SignalListItemFactory::new();
-
-		// FIXME: Find a better way than the pointer of the root widget to identify which component is hooked up to which GtkListItem
-		let controllers = Rc::new(RefCell::new(HashMap::<*mut GtkListItem, Controller<NameListRow>>::new()));
-
-		name_factory.connect_setup({
-			let sender = sender.clone();
-			let controllers = controllers.clone();
-			move |_, list_item| {
-				let controller = NameListRow::builder()
-					.launch(NameListRowInit {
-						name: Name {
-							name: "{none}".to_owned(),
-							gender: Gender::Both,
-						},
-						preference: None,
-					})
-					.forward(sender.input_sender(), |output| match output {
-						NameListRowOutput::NamePreferenceSet(name_with_preferences) => {
-							NameListInput::NamePreferenceUpdated(name_with_preferences)
-						}
-					});
-				list_item.set_child(Some(controller.widget()));
-				controllers.borrow_mut().insert(list_item.as_ptr(), controller);
-			}
-		});
-		name_factory.connect_bind({
-			let controllers = controllers.clone();
-			move |_, list_item| {
-				let controllers = controllers.borrow();
-				let controller = controllers
-					.get(&list_item.as_ptr())
-					.expect("No controller for this list item");
-
-				let item = list_item
-					.item()
-					.expect("Missing item")
-					.downcast::<BoxedAnyObject>()
-					.expect("Incorrect Type");
-				let name_with_preferences = item.borrow::<NameWithPreference>();
-
-				let _ = controller
-					.sender()
-					.send(NameListRowInput::SetName(name_with_preferences.clone()));
-			}
-		});
-
-		name_factory.connect_teardown(move |_, list_item| {
-			controllers.borrow_mut().remove(&list_item.as_ptr());
-		});
+		let name_factory = name_row_factory(sender.input_sender(), &backend);
 
 		let list_manager = DatabaseListManager::new(initial_filter, VIEW::default(), backend)
 			.expect("Failed to initialize name list manager");
@@ -185,17 +142,29 @@ where
 				.update_filter(filter)
 				.expect("Failed to update list manager"),
 			NamePreferenceUpdated(name_with_preferences) => {
-				let _ = sender.output(NameListOutput::NamePreferenceUpdated(name_with_preferences));
+				let _ = sender.output(NameListOutput::NamePreferencesUpdated(vec![name_with_preferences]));
 			}
-			MultiselectionPreferenceUpdated(NameWithPreference { preference, .. }) => {
+			MultiselectionPreferenceUpdated(NameWithPreference { preference, stars, .. }) => {
 				// TODO: Don't destroy the existing selection when applying the value
-				for NameWithPreference { name, gender, .. } in &self.selected_names {
-					let _ = sender.output(NameListOutput::NamePreferenceUpdated(NameWithPreference {
-						name: name.clone(),
-						gender: *gender,
+				let names = self
+					.selected_names
+					.iter()
+					.map(|name| NameWithPreference {
 						preference,
-					}));
-				}
+						// vetoed names keep their own stars
+						stars: if preference == Some(NamePreference::NoGo) {
+							name.stars
+						} else {
+							stars
+						},
+						..name.clone()
+					})
+					.collect();
+				// all at once, so the whole bulk change can be undone in one go
+				let _ = sender.output(NameListOutput::NamePreferencesUpdated(names));
+			}
+			GroupPreferenceUpdated { name, rating } => {
+				let _ = sender.output(NameListOutput::GroupPreferenceUpdated { name, rating });
 			}
 			SelectionChanged(selected_names) => {
 				self.selected_names = selected_names;
@@ -204,13 +173,12 @@ where
 					let _ = self
 						.selected_names_row_controller
 						.sender()
-						.send(NameListRowInput::SetName(NameWithPreference {
-							name: format!("{count} selected names"),
-							gender: Gender::Both,
-							preference: None,
-						}));
+						.send(NameListRowInput::SetName(placeholder_name(&format!(
+							"{count} selected names"
+						))));
 				}
 			}
+			Refresh => self.list_manager.notify_changed().expect("Failed to refresh names"),
 			RefreshRow { name } => {
 				self.list_manager
 					.notify_updated(&name)
@@ -220,18 +188,104 @@ where
 	}
 }
 
+fn name_row_factory<FILTER: 'static>(
+	input_sender: &Sender<NameListInput<FILTER>>,
+	backend: &Backend,
+) -> SignalListItemFactory {
+	let name_factory = SignalListItemFactory::new();
+
+	// FIXME: Find a better way than the pointer of the root widget to identify which component is hooked up to which GtkListItem
+	let controllers = Rc::new(RefCell::new(HashMap::<*mut GtkListItem, Controller<NameListRow>>::new()));
+
+	name_factory.connect_setup({
+		let input_sender = input_sender.clone();
+		let controllers = controllers.clone();
+		let backend = backend.clone();
+		move |_, list_item| {
+			let controller = NameListRow::builder()
+				.launch((
+					NameListRowInit {
+						name: placeholder_name("{none}"),
+						is_selection_summary: false,
+					},
+					backend.clone(),
+				))
+				.forward(&input_sender, |output| match output {
+					NameListRowOutput::NamePreferenceSet(name_with_preferences) => {
+						NameListInput::NamePreferenceUpdated(name_with_preferences)
+					}
+					NameListRowOutput::GroupPreferenceSet { name, rating } => {
+						NameListInput::GroupPreferenceUpdated { name, rating }
+					}
+				});
+			list_item.set_child(Some(controller.widget()));
+			controllers.borrow_mut().insert(list_item.as_ptr(), controller);
+		}
+	});
+	name_factory.connect_bind({
+		let controllers = controllers.clone();
+		move |_, list_item| {
+			let controllers = controllers.borrow();
+			let controller = controllers
+				.get(&list_item.as_ptr())
+				.expect("No controller for this list item");
+
+			let item = list_item
+				.item()
+				.expect("Missing item")
+				.downcast::<BoxedAnyObject>()
+				.expect("Incorrect Type");
+			let name_with_preferences = item.borrow::<NameWithPreference>();
+
+			let _ = controller
+				.sender()
+				.send(NameListRowInput::SetName(name_with_preferences.clone()));
+		}
+	});
+
+	name_factory.connect_teardown(move |_, list_item| {
+		controllers.borrow_mut().remove(&list_item.as_ptr());
+	});
+
+	name_factory
+}
+
+/// Stand-in for rows that don't show an actual name from the database (yet)
+fn placeholder_name(name: &str) -> NameWithPreference {
+	NameWithPreference {
+		name: name.to_owned(),
+		gender: Gender::Both,
+		preference: None,
+		stars: None,
+		birth_share: None,
+		variants: Default::default(),
+		has_notes: false,
+		tags: Default::default(),
+		due_for_review: false,
+	}
+}
+
 #[derive(Debug)]
 pub enum NameListInput<FILTER> {
 	UpdateFilter(FILTER),
 	NamePreferenceUpdated(NameWithPreference),
 	MultiselectionPreferenceUpdated(NameWithPreference),
+	GroupPreferenceUpdated {
+		name: String,
+		rating: Rating,
+	},
 	SelectionChanged(Vec<NameWithPreference>),
-	RefreshRow { name: String },
+	/// Reload all names, e.g. after switching to another project
+	Refresh,
+	RefreshRow {
+		name: String,
+	},
 }
 
 #[derive(Debug)]
 pub enum NameListOutput {
-	NamePreferenceUpdated(NameWithPreference),
+	NamePreferencesUpdated(Vec<NameWithPreference>),
+	GroupPreferenceUpdated { name: String, rating: Rating },
 }
 
 #[derive(Clone, Default)]
@@ -240,20 +294,35 @@ pub struct NameListView;
 #[derive(Clone, Debug)]
 pub struct NameListViewFilter {
 	pub gender: Gender,
-	pub show_favorite: bool,
-	pub show_nogo: bool,
-	pub show_undecided: bool,
-	pub name_contains: Option<String>,
+	pub preferences: PreferenceSelection,
+	pub search: NameSearch,
+	pub structure: StructureFilter,
+	pub exclude_similar_to_siblings: bool,
+	/// Only names with this tag
+	pub tag: Option<String>,
+	pub sort_order: SortOrder,
+}
+
+#[derive(Clone, Copy, Debug, Default, strum::EnumString, strum::VariantNames)]
+pub enum SortOrder {
+	#[default]
+	#[strum(serialize = "alphabetical")]
+	Alphabetical,
+	/// Best fit with the family surname first
+	#[strum(serialize = "surname fit")]
+	SurnameFit,
 }
 
 impl Default for NameListViewFilter {
 	fn default() -> Self {
 		Self {
 			gender: Gender::Both,
-			show_favorite: true,
-			show_nogo: true,
-			show_undecided: true,
-			name_contains: None,
+			preferences: PreferenceSelection::ALL,
+			search: NameSearch::default(),
+			structure: StructureFilter::default(),
+			exclude_similar_to_siblings: false,
+			tag: None,
+			sort_order: SortOrder::default(),
 		}
 	}
 }
@@ -267,24 +336,40 @@ impl DatabaseView for NameListView {
 		backend: &Backend,
 		NameListViewFilter {
 			gender,
-			show_favorite,
-			show_nogo,
-			show_undecided,
-			name_contains,
+			preferences,
+			search,
+			structure,
+			exclude_similar_to_siblings,
+			tag,
+			sort_order,
 		}: &Self::Filter,
 	) -> anyhow::Result<Vec<Self::Model>> {
-		Ok(backend.block_on_future(database::views::read_all_names(
+		let mut names = backend.block_on_future(database::views::read_all_names(
 			*gender,
-			*show_favorite,
-			*show_nogo,
-			*show_undecided,
-			name_contains.as_deref(),
+			*preferences,
+			search,
+			structure,
+			*exclude_similar_to_siblings,
+			tag.as_deref(),
+			backend.project().id,
 			backend.database_pool(),
-		))?)
+		))?;
+
+		if let (SortOrder::SurnameFit, Some(surname)) = (sort_order, backend.project().surname) {
+			names.sort_by_cached_key(|name| {
+				Reverse(SurnameCompatibility::between(&name.name, &surname, Language::default()).score())
+			});
+		}
+
+		Ok(names)
 	}
 
 	fn read_by_key(&self, backend: &Backend, key: &<Self::Model as Model>::Key) -> anyhow::Result<Self::Model> {
-		Ok(backend.block_on_future(database::views::read_one(key, backend.database_pool()))?)
+		Ok(backend.block_on_future(database::views::read_one(
+			key,
+			backend.project().id,
+			backend.database_pool(),
+		))?)
 	}
 }
 
@@ -1,72 +1,334 @@
-use crate::database::views::NameWithPreference;
-use crate::database::{Name, NamePreference};
+use crate::database;
+use crate::database::views::{NameMetadata, NameWithPreference, Nickname, PreferenceChange, SimilarName};
+use crate::database::{MAXIMUM_STARS, Project, Rating};
+use crate::gui::backend::Backend;
 use crate::gui::name_preference::{NamePreferenceInput, NamePreferenceView};
+use crate::initials::InitialsBlocklist;
+use crate::name_structure::Language;
+use crate::surname::SurnameCompatibility;
+use gtk::glib;
 use gtk::prelude::*;
+use gtk::{Align, Orientation};
 use relm4::{
 	Component, ComponentController, ComponentParts, ComponentSender, Controller, Sender, SimpleComponent, gtk,
 };
 
 pub struct NameListRow {
-	name: Name,
-	preference: Option<NamePreference>,
+	name: NameWithPreference,
 	preference_controller: Controller<NamePreferenceView>,
+	is_selection_summary: bool,
+	/// Only loaded once the "Similar names" expander is opened
+	similar_names: Option<String>,
+	/// Meaning, origin, name days and nicknames, only loaded once the "Details" expander is opened
+	details: Option<String>,
+	/// Only loaded once the notes popover is opened
+	notes: String,
+	/// Newest changes first, only loaded once the "History" expander is opened
+	history: Option<Vec<PreferenceChange>>,
+	/// The ratings after each change of `history`, to pick the one to restore
+	history_entries: gtk::StringList,
+	/// Family surname of the current project that the name is checked against, if one was configured
+	surname: Option<String>,
+	middle_name: Option<String>,
+	initials_blocklist: InitialsBlocklist,
+	backend: Backend,
 }
 
 #[relm4::component(pub)]
 impl SimpleComponent for NameListRow {
 	type Input = NameListRowInput;
 	type Output = NameListRowOutput;
-	type Init = NameListRowInit;
+	type Init = (NameListRowInit, Backend);
 
 	view! {
 		gtk::Box {
-			set_homogeneous: true,
+			set_orientation: Orientation::Vertical,
+
+			gtk::Box {
+				set_homogeneous: true,
+
+				#[name(name_label)]
+				gtk::Label {
+					set_use_markup: true,
+					#[watch]
+					set_label: &format!(r"<big><b>{}</b></big>", model.name.name),
+					#[watch]
+					set_tooltip_text: model.name.birth_share.map(|birth_share| birth_share.to_string()).as_deref(),
+				},
+
+				gtk::Image {
+					set_icon_name: Some("dialog-warning-symbolic"),
+					#[watch]
+					set_visible: !model.is_selection_summary && !model.blocked_initials().is_empty(),
+					#[watch]
+					set_tooltip_text: Some(&format!("Blocked initials: {}", model.blocked_initials().join(", "))),
+				},
+
+				gtk::MenuButton {
+					#[watch]
+					set_visible: !model.is_selection_summary,
+					#[watch]
+					set_icon_name: if model.name.has_notes { "document-edit-symbolic" } else { "document-new-symbolic" },
+					#[watch]
+					set_tooltip_text: Some(if model.name.has_notes { "Notes" } else { "Add a note" }),
+					set_halign: Align::Center,
+					set_valign: Align::Center,
+
+					#[wrap(Some)]
+					set_popover = &gtk::Popover {
+						connect_show[sender] => move |_| {
+							sender.input(NameListRowInput::NotesOpened);
+						},
+
+						gtk::Box {
+							set_orientation: Orientation::Vertical,
+							set_spacing: 6,
+
+							gtk::Label {
+								set_halign: Align::Start,
+								set_wrap: true,
+								set_max_width_chars: 40,
+								#[watch]
+								set_visible: !model.notes.is_empty(),
+								#[watch]
+								set_label: &model.notes,
+							},
+
+							gtk::Entry {
+								set_placeholder_text: Some("Add a note"),
+								connect_activate[sender] => move |entry| {
+									let note = entry.text().trim().to_owned();
+									if !note.is_empty() {
+										sender.input(NameListRowInput::AddNote(note));
+										entry.set_text("");
+									}
+								}
+							},
+						},
+					},
+				},
+
+				#[name(gender_label)]
+				gtk::Label {
+					#[watch]
+					set_label: model.name.gender.as_ref(),
+				},
+
+				gtk::Label {
+					#[watch]
+					set_visible: !model.is_selection_summary && model.surname.is_some(),
+					#[watch]
+					set_label: &model
+						.surname_compatibility()
+						.map(|compatibility| format!("Fit: {}", compatibility.score()))
+						.unwrap_or_default(),
+					#[watch]
+					set_tooltip_text: model
+						.surname_compatibility()
+						.map(|compatibility| {
+							let issues = compatibility.issues();
+							if issues.is_empty() {
+								"Goes well with the surname".to_owned()
+							} else {
+								issues.join("\n")
+							}
+						})
+						.as_deref(),
+				},
+
+				#[local]
+				preference_widget -> gtk::Box {},
+
+				gtk::Box {
+					gtk::Button {
+						set_icon_name: "edit-undo-symbolic",
+						set_vexpand: false,
+						set_hexpand: false,
+
+						connect_clicked[sender] => move |_| {
+							sender.input(NameListRowInput::UpdatePreference(Rating::default()));
+						}
+					},
+				}
+			},
 
-			#[name(name_label)]
 			gtk::Label {
+				set_halign: Align::Start,
+				set_wrap: true,
 				set_use_markup: true,
 				#[watch]
-				set_label: &format!(r"<big><b>{}</b></big>", model.name.name),
+				set_visible: !model.is_selection_summary && !model.name.tags.is_empty(),
+				#[watch]
+				set_label: &model.tag_chips(),
 			},
 
-			#[name(gender_label)]
-			gtk::Label {
+			gtk::Box {
+				set_orientation: Orientation::Horizontal,
+				set_spacing: 6,
 				#[watch]
-				set_label: model.name.gender.as_ref(),
-			},
+				set_visible: !model.is_selection_summary && model.name.due_for_review,
 
-			#[local]
-			preference_widget -> gtk::Box {},
+				gtk::Image {
+					set_icon_name: Some("alarm-symbolic"),
+				},
+				gtk::Label {
+					set_label: "Still a favorite?",
+				},
+				gtk::Button {
+					set_label: "Yes",
+					set_tooltip_text: Some("Comes up for a re-review again after twice as long as last time"),
+					connect_clicked[sender] => move |_| {
+						sender.input(NameListRowInput::ConfirmFavorite);
+					}
+				},
+				gtk::Button {
+					set_label: "Not sure",
+					set_tooltip_text: Some("Stays a favorite, but comes up for a re-review again soon"),
+					connect_clicked[sender] => move |_| {
+						sender.input(NameListRowInput::HesitateOnFavorite);
+					}
+				},
+			},
 
 			gtk::Box {
-				gtk::Button {
-					set_icon_name: "edit-undo-symbolic",
-					set_vexpand: false,
-					set_hexpand: false,
+				set_orientation: Orientation::Horizontal,
+				set_spacing: 6,
+				#[watch]
+				set_visible: !model.is_selection_summary && !model.name.variants.is_empty(),
 
+				gtk::Label {
+					set_halign: Align::Start,
+					set_wrap: true,
+					#[watch]
+					set_label: &format!("Variants: {}", model.name.variants.join(", ")),
+				},
+
+				gtk::Button {
+					set_label: "Apply preference to variants",
+					set_tooltip_text: Some("Give all spelling variants the same preference and rating as this name"),
 					connect_clicked[sender] => move |_| {
-						sender.input(NameListRowInput::UpdatePreference(None));
+						sender.input(NameListRowInput::ApplyPreferenceToVariants);
 					}
 				},
-			}
+			},
+
+			gtk::Expander {
+				set_label: Some("Details"),
+				#[watch]
+				set_visible: !model.is_selection_summary,
+				#[watch]
+				set_expanded: model.details.is_some(),
+				connect_expanded_notify[sender] => move |expander| {
+					sender.input(NameListRowInput::DetailsExpanded(expander.is_expanded()));
+				},
+
+				gtk::Label {
+					set_halign: Align::Start,
+					set_wrap: true,
+					#[watch]
+					set_label: model.details.as_deref().unwrap_or_default(),
+				},
+			},
+
+			gtk::Expander {
+				set_label: Some("History"),
+				#[watch]
+				set_visible: !model.is_selection_summary,
+				#[watch]
+				set_expanded: model.history.is_some(),
+				connect_expanded_notify[sender] => move |expander| {
+					sender.input(NameListRowInput::HistoryExpanded(expander.is_expanded()));
+				},
 
+				gtk::Box {
+					set_orientation: Orientation::Vertical,
+					set_spacing: 6,
+
+					gtk::Label {
+						set_halign: Align::Start,
+						set_wrap: true,
+						#[watch]
+						set_label: &model.timeline(),
+					},
+
+					gtk::Box {
+						set_orientation: Orientation::Horizontal,
+						set_spacing: 6,
+						#[watch]
+						set_visible: model.history.as_ref().is_some_and(|history| !history.is_empty()),
+
+						#[name(history_dropdown)]
+						gtk::DropDown {
+							set_model: Some(&model.history_entries),
+						},
+
+						gtk::Button {
+							set_label: "Restore",
+							set_tooltip_text: Some("Give the name the rating it had right after the selected change"),
+							connect_clicked[sender, history_dropdown] => move |_| {
+								sender.input(NameListRowInput::RestoreHistory(history_dropdown.selected()));
+							}
+						},
+					},
+				},
+			},
+
+			gtk::Expander {
+				set_label: Some("Similar names"),
+				#[watch]
+				set_visible: !model.is_selection_summary,
+				#[watch]
+				set_expanded: model.similar_names.is_some(),
+				connect_expanded_notify[sender] => move |expander| {
+					sender.input(NameListRowInput::SimilarNamesExpanded(expander.is_expanded()));
+				},
+
+				gtk::Label {
+					set_halign: Align::Start,
+					set_wrap: true,
+					#[watch]
+					set_label: model.similar_names.as_deref().unwrap_or_default(),
+				},
+			},
 		}
 	}
 
 	fn init(
-		NameListRowInit { name, preference }: Self::Init,
+		(
+			NameListRowInit {
+				name,
+				is_selection_summary,
+			},
+			backend,
+		): Self::Init,
 		root: Self::Root,
 		sender: ComponentSender<Self>,
 	) -> ComponentParts<Self> {
 		let preference_controller = NamePreferenceView::builder()
-			.launch(("Preference", None))
+			.launch(("Rating", MAXIMUM_STARS))
 			.forward(sender.input_sender(), NameListRowInput::UpdatePreference);
 		let preference_widget = preference_controller.widget().clone();
 
+		let Project {
+			surname, middle_name, ..
+		} = backend.project();
+		let initials_blocklist = backend
+			.block_on_future(database::read_initials_blocklist(backend.database_pool()))
+			.expect("Failed to read blocked initials");
+
 		let model = NameListRow {
 			name,
-			preference,
 			preference_controller,
+			is_selection_summary,
+			similar_names: None,
+			details: None,
+			notes: String::new(),
+			history: None,
+			history_entries: gtk::StringList::new(&[]),
+			surname,
+			middle_name,
+			initials_blocklist,
+			backend,
 		};
 
 		let widgets = view_output!();
@@ -77,59 +339,271 @@ impl SimpleComponent for NameListRow {
 	fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>) {
 		use NameListRowInput::*;
 		match message {
-			SetName(NameWithPreference {
-				name,
-				gender,
-				preference,
-			}) => {
-				self.name = Name { name, gender };
-				self.preference = preference;
-				let _ = self
-					.preference_controller
-					.sender()
-					.send(NamePreferenceInput::SetPreference(preference));
+			SetName(name) => {
+				// the project might have been switched since the row was last used
+				let Project {
+					surname, middle_name, ..
+				} = self.backend.project();
+				self.name = name;
+				self.similar_names = None;
+				self.details = None;
+				self.notes.clear();
+				self.history = None;
+				self.surname = surname;
+				self.middle_name = middle_name;
 			}
-			UpdatePreference(preference) => {
-				self.preference = preference;
-				let _ = self
-					.preference_controller
-					.sender()
-					.send(NamePreferenceInput::SetPreference(preference));
-
+			UpdatePreference(Rating { preference, stars }) => {
+				self.name.preference = preference;
+				self.name.stars = stars;
 				self.send_preference_output(sender.output_sender());
 			}
+			ApplyPreferenceToVariants => {
+				let _ = sender.output(NameListRowOutput::GroupPreferenceSet {
+					name: self.name.name.clone(),
+					rating: self.name.rating(),
+				});
+			}
+			ConfirmFavorite => self.review_favorite(true),
+			HesitateOnFavorite => self.review_favorite(false),
+			NotesOpened => self.load_notes(),
+			AddNote(note) => {
+				self.backend
+					.block_on_future(database::insert_name_note(
+						&self.name.name,
+						self.backend.voter(),
+						&note,
+						self.backend.project().id,
+						self.backend.database_pool(),
+					))
+					.expect("Failed to add note");
+				self.name.has_notes = true;
+				self.load_notes();
+			}
+			DetailsExpanded(false) => self.details = None,
+			DetailsExpanded(true) => self.load_details(),
+			HistoryExpanded(false) => self.history = None,
+			HistoryExpanded(true) => self.load_history(),
+			RestoreHistory(index) => {
+				let Some(change) = self
+					.history
+					.as_ref()
+					.and_then(|history| history.get(usize::try_from(index).ok()?))
+				else {
+					return;
+				};
+				let Rating { preference, stars } = change.new_rating();
+				self.name.preference = preference;
+				self.name.stars = stars;
+				self.send_preference_output(sender.output_sender());
+			}
+			SimilarNamesExpanded(false) => self.similar_names = None,
+			SimilarNamesExpanded(true) => {
+				const SIMILAR_NAMES_COUNT: usize = 10;
+				let similar_names = self
+					.backend
+					.block_on_future(database::views::read_similar_names(
+						&self.name.name,
+						SIMILAR_NAMES_COUNT,
+						self.backend.database_pool(),
+					))
+					.expect("Failed to read similar names");
+				self.similar_names = Some(if similar_names.is_empty() {
+					"No similar names found".to_owned()
+				} else {
+					similar_names
+						.into_iter()
+						.map(|SimilarName { name, .. }| name)
+						.collect::<Vec<_>>()
+						.join(", ")
+				});
+			}
 		}
 
 		let _ = self
 			.preference_controller
 			.sender()
-			.send(NamePreferenceInput::SetPreference(self.preference));
+			.send(NamePreferenceInput::SetRating(self.name.rating()));
 	}
 }
 
 impl NameListRow {
+	fn blocked_initials(&self) -> Vec<String> {
+		let Some(surname) = self.surname.as_deref() else {
+			return Vec::new();
+		};
+		self.initials_blocklist
+			.flags(&self.name.name, self.middle_name.as_deref(), surname)
+	}
+
+	fn surname_compatibility(&self) -> Option<SurnameCompatibility> {
+		let surname = self.surname.as_deref()?;
+		Some(SurnameCompatibility::between(
+			&self.name.name,
+			surname,
+			Language::default(),
+		))
+	}
+
+	/// Pango markup showing every tag as a small colored chip
+	fn tag_chips(&self) -> String {
+		self.name
+			.tags
+			.iter()
+			.map(|tag| {
+				format!(
+					r#"<span background="{TAG_CHIP_COLOR}" foreground="white"><small> {} </small></span>"#,
+					glib::markup_escape_text(tag)
+				)
+			})
+			.collect::<Vec<_>>()
+			.join(" ")
+	}
+
+	fn load_details(&mut self) {
+		let metadata = self
+			.backend
+			.block_on_future(database::views::read_name_metadata(
+				&self.name.name,
+				self.backend.database_pool(),
+			))
+			.expect("Failed to read name metadata");
+		let nicknames = self
+			.backend
+			.block_on_future(database::views::read_nicknames(
+				&self.name.name,
+				Language::default(),
+				self.backend.project().id,
+				self.backend.database_pool(),
+			))
+			.expect("Failed to read nicknames");
+		self.details = Some(describe_details(metadata, &nicknames));
+	}
+
+	fn load_notes(&mut self) {
+		let notes = self
+			.backend
+			.block_on_future(database::views::read_name_notes(
+				&self.name.name,
+				self.backend.project().id,
+				self.backend.database_pool(),
+			))
+			.expect("Failed to read notes");
+		self.notes = notes.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n");
+	}
+
+	/// Either confirms the favorite or starts its re-review schedule from the beginning
+	fn review_favorite(&mut self, confirmed: bool) {
+		let name = &self.name.name;
+		let project_id = self.backend.project().id;
+		let database_pool = self.backend.database_pool();
+		self.backend
+			.block_on_future(async {
+				if confirmed {
+					database::confirm_favorite(name, project_id, database_pool).await
+				} else {
+					database::hesitate_on_favorite(name, project_id, database_pool).await
+				}
+			})
+			.expect("Failed to review favorite");
+		self.name.due_for_review = false;
+	}
+
+	fn load_history(&mut self) {
+		let mut history = self
+			.backend
+			.block_on_future(database::views::read_preference_history(
+				Some(&self.name.name),
+				self.backend.project().id,
+				self.backend.database_pool(),
+			))
+			.expect("Failed to read preference history");
+		history.reverse();
+
+		let entries = history
+			.iter()
+			.map(|change| format!("{}: {}", change.local_changed_at(), change.new_rating()))
+			.collect::<Vec<_>>();
+		let entries = entries.iter().map(String::as_str).collect::<Vec<_>>();
+		self.history_entries.splice(0, self.history_entries.n_items(), &entries);
+		self.history = Some(history);
+	}
+
+	fn timeline(&self) -> String {
+		match &self.history {
+			Some(history) if history.is_empty() => "No changes recorded yet".to_owned(),
+			Some(history) => history.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"),
+			None => String::new(),
+		}
+	}
+
 	fn send_preference_output(&self, sender: &Sender<NameListRowOutput>) {
-		let _ = sender.send(NameListRowOutput::NamePreferenceSet(NameWithPreference {
-			name: self.name.name.clone(),
-			gender: self.name.gender,
-			preference: self.preference,
-		}));
+		let _ = sender.send(NameListRowOutput::NamePreferenceSet(self.name.clone()));
+	}
+}
+
+const TAG_CHIP_COLOR: &str = "#3584e4";
+
+fn describe_details(metadata: NameMetadata, nicknames: &[Nickname]) -> String {
+	if metadata.is_empty() && nicknames.is_empty() {
+		return "No details imported".to_owned();
+	}
+
+	let NameMetadata {
+		meaning,
+		origin,
+		name_days,
+	} = metadata;
+	let mut lines = Vec::new();
+	if let Some(meaning) = meaning {
+		lines.push(format!("Meaning: {meaning}"));
+	}
+	if let Some(origin) = origin {
+		lines.push(format!("Origin: {origin}"));
+	}
+	for name_day in name_days {
+		lines.push(format!("Name day: {name_day}"));
+	}
+	if !nicknames.is_empty() {
+		lines.push(format!(
+			"Nicknames: {}",
+			nicknames
+				.iter()
+				.map(|nickname| nickname.name.as_str())
+				.collect::<Vec<_>>()
+				.join(", ")
+		));
+	}
+	for nickname in nicknames.iter().filter(|nickname| nickname.no_go) {
+		lines.push(format!("⚠ {} is on the no-go list", nickname.name));
 	}
+	lines.join("\n")
 }
 
 #[derive(Debug)]
 pub struct NameListRowInit {
-	pub name: Name,
-	pub preference: Option<NamePreference>,
+	pub name: NameWithPreference,
+	/// The row used for changing all selected names at once, which doesn't stand for a single name
+	pub is_selection_summary: bool,
 }
 
 #[derive(Debug)]
 pub enum NameListRowInput {
 	SetName(NameWithPreference),
-	UpdatePreference(Option<NamePreference>),
+	UpdatePreference(Rating),
+	ApplyPreferenceToVariants,
+	ConfirmFavorite,
+	HesitateOnFavorite,
+	NotesOpened,
+	AddNote(String),
+	DetailsExpanded(bool),
+	HistoryExpanded(bool),
+	/// Index into the history, newest first
+	RestoreHistory(u32),
+	SimilarNamesExpanded(bool),
 }
 
 #[derive(Debug)]
 pub enum NameListRowOutput {
 	NamePreferenceSet(NameWithPreference),
+	GroupPreferenceSet { name: String, rating: Rating },
 }
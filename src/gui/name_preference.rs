@@ -1,17 +1,21 @@
-use crate::database::NamePreference;
+use crate::database::{NamePreference, Rating};
 use gtk::prelude::*;
 use gtk::{Align, Orientation};
 use relm4::{ComponentParts, ComponentSender, SimpleComponent, gtk};
 
+/// A row of stars to rate with and a button to veto, a single star simply marks a favorite.
 pub struct NamePreferenceView {
-	preference: Option<NamePreference>,
+	rating: Rating,
+	maximum_stars: u8,
+	star_buttons: Vec<gtk::Button>,
 }
 
 #[relm4::component(pub)]
 impl SimpleComponent for NamePreferenceView {
 	type Input = NamePreferenceInput;
-	type Output = Option<NamePreference>;
-	type Init = (&'static str, Option<NamePreference>);
+	type Output = Rating;
+	/// Label and number of stars
+	type Init = (&'static str, u8);
 
 	view! {
 		gtk::Box {
@@ -25,73 +29,137 @@ impl SimpleComponent for NamePreferenceView {
 				set_orientation: Orientation::Horizontal,
 				set_halign: Align::Center,
 
-				#[name(favorite_button)]
-				gtk::CheckButton {
+				#[name(star_box)]
+				gtk::Box {
+					set_orientation: Orientation::Horizontal,
+					// the stars of a vetoed name are kept, but don't count for now
 					#[watch]
-					set_active: model.preference == Some(NamePreference::Favorite),
-					#[watch]
-					set_inconsistent: model.preference.is_none(),
-					connect_toggled[sender] => move |button| {
-						if button.is_active() {
-							sender.input(NamePreferenceInput::PreferenceToggled(NamePreference::Favorite));
-						}
-					}
+					set_opacity: if model.rating.preference == Some(NamePreference::NoGo) { 0.5 } else { 1.0 },
 				},
-				gtk::Image {
-					set_icon_name: Some("emblem-favorite-symbolic"),
-				},
-				#[name(nogo_button)]
-				gtk::CheckButton {
-					set_group: Some(&favorite_button),
-					#[watch]
-					set_active: model.preference == Some(NamePreference::NoGo),
+				gtk::ToggleButton {
+					set_icon_name: "action-unavailable-symbolic",
+					set_tooltip_text: Some("No-go"),
+					set_has_frame: false,
 					#[watch]
-					set_inconsistent: model.preference.is_none(),
+					set_active: model.rating.preference == Some(NamePreference::NoGo),
 					connect_toggled[sender] => move |button| {
-						if button.is_active() {
-							sender.input(NamePreferenceInput::PreferenceToggled(NamePreference::NoGo));
-						}
+						sender.input(NamePreferenceInput::VetoToggled(button.is_active()));
 					}
 				},
-				gtk::Image {
-					set_icon_name: Some("action-unavailable-symbolic"),
-				},
 			},
 		}
 	}
 
 	fn init(
-		(parent_name, preference): Self::Init,
+		(parent_name, maximum_stars): Self::Init,
 		root: Self::Root,
 		sender: ComponentSender<Self>,
 	) -> ComponentParts<Self> {
-		let model = Self { preference };
+		let star_buttons = (1..=maximum_stars)
+			.map(|stars| {
+				let button = gtk::Button::builder()
+					.icon_name("non-starred-symbolic")
+					.tooltip_text(if stars == 1 {
+						"1 star".to_owned()
+					} else {
+						format!("{stars} stars")
+					})
+					.has_frame(false)
+					.build();
+				let sender = sender.clone();
+				button.connect_clicked(move |_| sender.input(NamePreferenceInput::StarClicked(stars)));
+				button
+			})
+			.collect();
+		let model = Self {
+			rating: Rating::default(),
+			maximum_stars,
+			star_buttons,
+		};
 
 		let widgets = view_output!();
+		for button in &model.star_buttons {
+			widgets.star_box.append(button);
+		}
 
 		ComponentParts { widgets, model }
 	}
 
 	fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>) {
 		use NamePreferenceInput::*;
-		match message {
-			SetPreference(preference) => {
-				self.preference = preference;
+		let rating = match message {
+			SetRating(rating) => {
+				self.rating = rating;
+				self.update_stars();
+				return;
 			}
-			PreferenceToggled(preference) => {
-				if Some(preference) == self.preference {
+			StarClicked(stars) => {
+				if self.rating.preference == Some(NamePreference::Favorite) && self.shown_stars() == stars {
+					// clicking the current rating again takes it back
+					Rating::default()
+				} else if self.rating.preference == Some(NamePreference::NoGo) {
+					// rating doesn't lift a veto, the stars are kept for when it is
+					Rating {
+						preference: Some(NamePreference::NoGo),
+						stars: Some(stars),
+					}
+				} else {
+					Rating {
+						preference: Some(NamePreference::Favorite),
+						stars: Some(stars),
+					}
+				}
+			}
+			VetoToggled(vetoed) => {
+				if vetoed == (self.rating.preference == Some(NamePreference::NoGo)) {
 					return;
 				}
 
-				self.preference = Some(preference);
-				let _ = sender.output(Some(preference));
+				Rating {
+					preference: if vetoed {
+						Some(NamePreference::NoGo)
+					} else {
+						self.rating.stars.map(|_| NamePreference::Favorite)
+					},
+					stars: self.rating.stars,
+				}
 			}
+		};
+
+		self.rating = rating;
+		self.update_stars();
+		let _ = sender.output(rating);
+	}
+}
+
+impl NamePreferenceView {
+	/// Favorites that were never rated count as fully rated
+	fn shown_stars(&self) -> u8 {
+		match self.rating {
+			Rating { stars: Some(stars), .. } => stars.min(self.maximum_stars),
+			Rating {
+				preference: Some(NamePreference::Favorite),
+				stars: None,
+			} => self.maximum_stars,
+			Rating { .. } => 0,
+		}
+	}
+
+	fn update_stars(&self) {
+		let shown_stars = usize::from(self.shown_stars());
+		for (index, button) in self.star_buttons.iter().enumerate() {
+			button.set_icon_name(if index < shown_stars {
+				"starred-symbolic"
+			} else {
+				"non-starred-symbolic"
+			});
 		}
 	}
 }
 
 #[derive(Debug)]
 pub enum NamePreferenceInput {
-	SetPreference(Option<NamePreference>),
-	PreferenceToggled(NamePreference),
+	SetRating(Rating),
+	StarClicked(u8),
+	VetoToggled(bool),
 }
@@ -0,0 +1,53 @@
+use crate::database::{Rating, RatingChange};
+
+/// Older changes are forgotten so the stack doesn't grow for the whole session
+const MAXIMUM_UNDO_STEPS: usize = 100;
+
+/// Preference changes that can be undone and redone, a bulk change is a single step.
+#[derive(Debug, Default)]
+pub struct UndoStack {
+	undo: Vec<Vec<RatingChange>>,
+	redo: Vec<Vec<RatingChange>>,
+}
+
+impl UndoStack {
+	/// A new change makes everything that was undone before impossible to redo
+	pub fn push(&mut self, changes: Vec<RatingChange>) {
+		if changes.is_empty() {
+			return;
+		}
+
+		if self.undo.len() == MAXIMUM_UNDO_STEPS {
+			self.undo.remove(0);
+		}
+		self.undo.push(changes);
+		self.redo.clear();
+	}
+
+	/// The ratings to write for undoing the last change
+	pub fn undo(&mut self) -> Option<Vec<(String, Rating)>> {
+		let changes = self.undo.pop()?;
+		let ratings = changes
+			.iter()
+			.map(|change| (change.name.clone(), change.before))
+			.collect();
+		self.redo.push(changes);
+		Some(ratings)
+	}
+
+	/// The ratings to write for redoing the last undone change
+	pub fn redo(&mut self) -> Option<Vec<(String, Rating)>> {
+		let changes = self.redo.pop()?;
+		let ratings = changes
+			.iter()
+			.map(|change| (change.name.clone(), change.after))
+			.collect();
+		self.undo.push(changes);
+		Some(ratings)
+	}
+
+	pub fn clear(&mut self) {
+		self.undo.clear();
+		self.redo.clear();
+	}
+}
@@ -0,0 +1,86 @@
+//! Checks whether the initials of a full name spell something that a child shouldn't be stuck with, like "A.S.S.".
+
+use crate::name_normalization::fold_name;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+static BUNDLED_BLOCKLIST: LazyLock<InitialsBlocklist> = LazyLock::new(|| InitialsBlocklist {
+	blocked_initials: include_str!("initials_blocklist.txt")
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with('#'))
+		.map(str::to_owned)
+		.collect(),
+});
+
+#[derive(Clone, Debug)]
+pub struct InitialsBlocklist {
+	blocked_initials: HashSet<String>,
+}
+
+impl InitialsBlocklist {
+	/// The blocklist that ships with the application, see `initials_blocklist.txt`
+	pub fn bundled() -> &'static Self {
+		&BUNDLED_BLOCKLIST
+	}
+
+	/// The bundled blocklist extended by user defined initials
+	pub fn with_additional(additional_initials: impl IntoIterator<Item = String>) -> Self {
+		let mut blocklist = Self::bundled().clone();
+		blocklist.blocked_initials.extend(
+			additional_initials
+				.into_iter()
+				.map(|initials| normalize_initials(&initials)),
+		);
+		blocklist
+	}
+
+	/// Initials without dots or spaces, e.g. `KZ` or `k.z.`
+	pub fn contains(&self, initials: &str) -> bool {
+		self.blocked_initials.contains(&normalize_initials(initials))
+	}
+
+	/// All blocked combinations of initials of a full name, both the full monogram and the pair of first name and
+	/// surname, formatted like "A.S.S.".
+	pub fn flags(&self, first_name: &str, middle_name: Option<&str>, surname: &str) -> Vec<String> {
+		let first_and_surname = [first_name, surname];
+		let mut flags = Vec::new();
+		if let Some(middle_name) = middle_name {
+			let monogram = [first_name, middle_name, surname];
+			if self.contains(&initials(&monogram)) {
+				flags.push(dotted_initials(&monogram));
+			}
+		}
+		if self.contains(&initials(&first_and_surname)) {
+			flags.push(dotted_initials(&first_and_surname));
+		}
+		flags
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &str> {
+		self.blocked_initials.iter().map(String::as_str)
+	}
+}
+
+/// Uppercase initials without diacritics, e.g. "AOE" for "Ännie Otto Ewald"
+pub fn initials(names: &[&str]) -> String {
+	names
+		.iter()
+		.filter_map(|name| name.chars().find(|character| character.is_alphabetic()))
+		.map(|initial| fold_name(&initial.to_string()).to_uppercase())
+		.collect()
+}
+
+/// Initials like they would be written, e.g. "A.S.S."
+pub fn dotted_initials(names: &[&str]) -> String {
+	initials(names).chars().flat_map(|initial| [initial, '.']).collect()
+}
+
+/// Uppercase letters only, so "a.s.s." and "ASS" are the same initials
+pub fn normalize_initials(initials: &str) -> String {
+	initials
+		.chars()
+		.filter(|character| character.is_alphabetic())
+		.map(|initial| fold_name(&initial.to_string()).to_uppercase())
+		.collect()
+}
@@ -1,16 +1,55 @@
-use crate::csv_parser::{Gender, parse_csv};
-use crate::utils::stream_blocking_iterator;
+use crate::combinations::{Combination, CombinationAnalysis};
+use crate::csv_parser::{Gender, NameRecord, parse_csv};
+use crate::database::views::{NameSearch, PreferenceSelection};
+use crate::database::{ImportedNote, MAXIMUM_STARS, Name, NamePreference, Project, Rating};
+use crate::initials::{InitialsBlocklist, normalize_initials};
+use crate::name_metadata::parse_metadata_csv;
+use crate::name_normalization::normalize_name;
+use crate::name_structure::{Language, StructureFilter};
+use crate::nicknames::parse_nicknames_csv;
+use crate::phonetics::{PhoneticCode, phonetic_codes};
+use crate::popularity::{BirthShare, year_of_source};
+use crate::preference_exchange::{ExchangeFormat, ImportStrategy, NoteRecord, PreferenceRecord};
+use crate::preference_merge::MergeStrategy;
+use crate::search_query::{Predicate, SearchQuery, Term};
+use crate::siblings::SiblingHarmony;
+use crate::similarity::levenshtein;
+use crate::surname::SurnameCompatibility;
+use crate::sync::Changeset;
+use crate::utils::{PrettyPrintedDuration, stream_blocking_iterator};
 use anyhow::Context;
+use chrono::{Local, NaiveDate};
 use clap::Parser;
 use futures_util::TryStreamExt;
 use sqlx::SqlitePool;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
 use std::future;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 use tokio::runtime;
 
+mod combinations;
 mod csv_parser;
 mod database;
 mod gui;
+mod initials;
+mod name_groups;
+mod name_metadata;
+mod name_normalization;
+mod name_structure;
+mod nicknames;
+mod phonetics;
+mod popularity;
+mod preference_exchange;
+mod preference_merge;
+mod search_query;
+mod siblings;
+mod similarity;
+mod surname;
+mod sync;
 mod utils;
 
 fn main() -> anyhow::Result<()> {
@@ -27,25 +66,407 @@ fn main() -> anyhow::Result<()> {
 struct Cli {
 	#[clap(long, env = "DATABASE_PATH")]
 	database_path: PathBuf,
+	/// The project whose preferences, surname and siblings are used
+	#[clap(long, env = "PROJECT", default_value = DEFAULT_PROJECT)]
+	project: String,
+	/// Who is using the tool, recorded with notes and preference changes
+	#[clap(long, env = "VOTER", global = true)]
+	voter: Option<String>,
 	#[clap(subcommand)]
 	command: Command,
 }
 
 #[derive(Debug, Parser)]
 enum Command {
-	Parse { name_list: PathBuf },
-	Ingest { name_list: PathBuf },
-	ListAll { gender: Gender },
-	Random { gender: Gender },
+	Parse {
+		name_list: PathBuf,
+	},
+	Ingest {
+		name_list: PathBuf,
+	},
+	/// Import meaning, origin and name days of names that were already ingested, see `src/name_metadata.rs` for the
+	/// CSV format
+	ImportMetadata {
+		metadata: PathBuf,
+	},
+	/// Import nicknames of names that were already ingested, see `src/nicknames.rs` for the CSV format
+	ImportNicknames {
+		nicknames: PathBuf,
+	},
+	ListAll {
+		gender: Gender,
+		/// Search query, e.g. `len:<6 starts:a pref:undecided pop:>100 -contains:ie` or `origin:greek nameday:spring`
+		#[clap(long)]
+		query: Option<SearchQuery>,
+		#[clap(flatten)]
+		structure: StructureFilter,
+		/// Leave out names that are easily confused with the name of a sibling
+		#[clap(long)]
+		exclude_similar_to_siblings: bool,
+		/// Only list the favorites that are due for a re-review, see `review`
+		#[clap(long)]
+		due_for_review: bool,
+	},
+	Random {
+		gender: Gender,
+	},
+	/// Show the statistics, meaning, origin and name days of a name
+	Show {
+		name: String,
+	},
+	/// Rate a name from 1 to 5 stars, which makes it a favorite unless it is a no-go
+	Rate {
+		name: String,
+		#[clap(value_parser = clap::value_parser!(u8).range(1..=i64::from(MAXIMUM_STARS)))]
+		stars: u8,
+	},
+	/// Make names favorites, they keep their stars if they had some
+	Favorite {
+		#[clap(flatten)]
+		selection: BulkSelection,
+	},
+	/// Veto names
+	Nogo {
+		#[clap(flatten)]
+		selection: BulkSelection,
+	},
+	/// Reset names to undecided
+	Clear {
+		#[clap(flatten)]
+		selection: BulkSelection,
+	},
+	/// List names that are pronounced similarly, using Kölner Phonetik and Double Metaphone
+	SoundsLike {
+		name: String,
+	},
+	/// Typo tolerant search, best matches first
+	Search {
+		term: String,
+		#[clap(long, default_value_t = 20)]
+		limit: usize,
+	},
+	/// Imported and derived nicknames of a name, warning about the ones that are on the no-go list
+	Nicknames {
+		name: String,
+		/// Language of the diminutive patterns
+		#[clap(long, default_value_t)]
+		language: Language,
+	},
+	/// Names that are spelled similarly
+	Similar {
+		name: String,
+		#[clap(long, short = 'n', default_value_t = 10)]
+		count: usize,
+	},
+	/// Show or change the family surname that first names are checked against
+	Surname {
+		surname: Option<String>,
+		/// Remove the configured surname
+		#[clap(long, conflicts_with = "surname")]
+		clear: bool,
+	},
+	/// Check how well a first name goes together with the family surname
+	Check {
+		name: String,
+		/// Language used for estimating the number of syllables
+		#[clap(long, default_value_t)]
+		language: Language,
+	},
+	/// Configure which initials first names are checked for, together with the surname and middle name
+	Initials {
+		#[clap(subcommand)]
+		command: InitialsCommand,
+	},
+	/// First + middle name combinations of the favorite names
+	Combinations {
+		#[clap(subcommand)]
+		command: CombinationsCommand,
+	},
+	/// Manage the names of older siblings and check how well names go with them
+	Siblings {
+		#[clap(subcommand)]
+		command: SiblingsCommand,
+	},
+	/// Tag names, like "classic" or "works in English"
+	Tags {
+		#[clap(subcommand)]
+		command: TagsCommand,
+	},
+	/// Notes about names, like why a name was vetoed
+	Notes {
+		#[clap(subcommand)]
+		command: NotesCommand,
+	},
+	/// Re-review favorites once in a while, list the ones that are due with `list-all both --due-for-review`
+	Review {
+		#[clap(subcommand)]
+		command: ReviewCommand,
+	},
+	/// How the preferences of names changed over time
+	History {
+		#[clap(subcommand)]
+		command: HistoryCommand,
+	},
+	/// Manage groups of spelling variants, like "Maria" and "Marie"
+	Group {
+		#[clap(subcommand)]
+		command: GroupCommand,
+	},
+	/// Manage projects, every baby gets its own preferences, surname and siblings
+	Projects {
+		#[clap(subcommand)]
+		command: ProjectsCommand,
+	},
+	/// Export the preferences, star ratings and notes of the project, see `src/preference_exchange.rs` for the formats
+	ExportPreferences {
+		#[clap(long, default_value_t = ExchangeFormat::Csv)]
+		format: ExchangeFormat,
+		/// Write to this file instead of stdout
+		#[clap(long, short = 'o')]
+		output: Option<PathBuf>,
+	},
+	/// Import preferences, star ratings and notes that were exported with `export-preferences`, notes without a voter
+	/// are attributed to `--voter`
+	ImportPreferences {
+		path: PathBuf,
+		/// Guessed from the file extension by default
+		#[clap(long)]
+		format: Option<ExchangeFormat>,
+		/// What happens to names that already have a preference: `overwrite`, `keep-existing` or `only-undecided`
+		#[clap(long, default_value_t)]
+		strategy: ImportStrategy,
+	},
+	/// Merge the preferences of the same project in another database file, e.g. from the laptop of the other parent.
+	/// Both databases need to be opened with the same version before.
+	Merge {
+		other_database: PathBuf,
+		/// How names with different ratings in both databases are resolved: `theirs`, `ours`, `favorite-wins`,
+		/// `veto-wins` or `separate-voters`, which also keeps the ratings of `--voter` and `--other-voter` separately
+		#[clap(long, default_value_t)]
+		strategy: MergeStrategy,
+		/// Who rated the names in the other database, recorded in the history. Defaults to the file name of the other
+		/// database.
+		#[clap(long)]
+		other_voter: Option<String>,
+	},
+	/// Keep the preferences of copies of the database in sync through files, e.g. on a USB stick or in a shared folder
+	Sync {
+		#[clap(subcommand)]
+		command: SyncCommand,
+	},
+	/// Recalculate the phonetic codes of all names, e.g. for databases ingested before they were introduced
+	Reindex,
 	Gui,
 }
 
+/// The names whose preference is changed at once, given directly, in a file or by a filter. Filters select from all
+/// names, so they can't be combined with names given directly or in a file.
+#[derive(Debug, clap::Args)]
+struct BulkSelection {
+	names: Vec<String>,
+	/// A file with one name per line
+	#[clap(long)]
+	file: Option<PathBuf>,
+	/// Select all names of this gender, `both` selects names of any gender
+	#[clap(long, conflicts_with_all = ["names", "file"])]
+	gender: Option<Gender>,
+	/// Select all names containing this text
+	#[clap(long, conflicts_with_all = ["names", "file"])]
+	contains: Option<String>,
+	/// Select all names ending with this text
+	#[clap(long, conflicts_with_all = ["names", "file"])]
+	ends_with: Option<String>,
+	/// Leave out names that already have a preference
+	#[clap(long)]
+	undecided_only: bool,
+	/// Only list what would change
+	#[clap(long)]
+	dry_run: bool,
+	/// Don't ask for confirmation
+	#[clap(long, short = 'y')]
+	yes: bool,
+}
+
+#[derive(Debug, Parser)]
+enum InitialsCommand {
+	/// Show or change the middle name that is part of the initials
+	MiddleName {
+		middle_name: Option<String>,
+		/// Remove the configured middle name
+		#[clap(long, conflicts_with = "middle_name")]
+		clear: bool,
+	},
+	/// Flag names with these initials, e.g. `abc` or `A.B.C.`
+	Block { initials: String },
+	/// Remove initials that were added with `block`, the bundled ones can't be removed
+	Unblock { initials: String },
+	/// List all blocked initials
+	List,
+}
+
+#[derive(Debug, Parser)]
+enum CombinationsCommand {
+	/// List all combinations of two favorite names, best fit with the surname first
+	List {
+		/// Also list the combinations as hyphenated double names like "Anna-Lena"
+		#[clap(long)]
+		hyphenated: bool,
+		/// Language used for estimating the number of syllables
+		#[clap(long, default_value_t)]
+		language: Language,
+	},
+	/// Rate a combination, no preference resets it to undecided
+	Rate {
+		first_name: String,
+		middle_name: String,
+		#[clap(long)]
+		hyphenated: bool,
+		preference: Option<NamePreference>,
+	},
+}
+
+#[derive(Debug, Parser)]
+enum SiblingsCommand {
+	Add {
+		name: String,
+	},
+	Remove {
+		name: String,
+	},
+	List,
+	/// Check how well a name goes together with the names of the siblings
+	Check {
+		name: String,
+		/// Language used for estimating the number of syllables
+		#[clap(long, default_value_t)]
+		language: Language,
+	},
+}
+
+#[derive(Debug, Parser)]
+enum ProjectsCommand {
+	List,
+	Add {
+		name: String,
+		#[clap(long)]
+		surname: Option<String>,
+		/// Only list names for this gender
+		#[clap(long, default_value = "both")]
+		gender: Gender,
+		/// e.g. `2027-03-14`
+		#[clap(long)]
+		due_date: Option<NaiveDate>,
+	},
+	/// Change the gender or due date of a project, the surname is changed with the `surname` command
+	Update {
+		name: String,
+		#[clap(long)]
+		gender: Option<Gender>,
+		#[clap(long)]
+		due_date: Option<NaiveDate>,
+		#[clap(long, conflicts_with = "due_date")]
+		clear_due_date: bool,
+	},
+	/// Delete a project together with all of its preferences
+	Remove {
+		name: String,
+	},
+}
+
+#[derive(Debug, Parser)]
+enum TagsCommand {
+	/// Tag the given names and/or all names matching the search query
+	Add {
+		tag: String,
+		names: Vec<String>,
+		/// e.g. `--query "starts:ma len:<5"`
+		#[clap(long)]
+		query: Option<SearchQuery>,
+	},
+	/// Untag the given names and/or all names matching the search query
+	Remove {
+		tag: String,
+		names: Vec<String>,
+		#[clap(long)]
+		query: Option<SearchQuery>,
+	},
+	/// List all tags, the tagged names can be listed with `list-all both --query tag:<tag>`
+	List,
+	/// Delete a tag and remove it from all names
+	Delete { tag: String },
+}
+
+#[derive(Debug, Parser)]
+enum NotesCommand {
+	/// Add a note, written by `--voter` if given
+	Add { name: String, note: String },
+	/// List the notes of a name, or of all names
+	List { name: Option<String> },
+	/// Delete a note by the id shown by `list`
+	Delete { id: i64 },
+}
+
+#[derive(Debug, Parser)]
+enum ReviewCommand {
+	/// Still a favorite, it comes up for a re-review again after twice as long as last time
+	Confirm { name: String },
+	/// Not so sure anymore, it stays a favorite but comes up for a re-review again soon
+	Hesitate { name: String },
+}
+
+#[derive(Debug, Parser)]
+enum HistoryCommand {
+	/// List the preference changes of a name, or of all names, oldest first
+	List { name: Option<String> },
+	/// Give a name the rating it had right after the change with the id shown by `list`
+	Restore { id: i64 },
+}
+
+#[derive(Debug, Parser)]
+enum SyncCommand {
+	/// Write the preference changes since the last export to a file
+	Export {
+		path: PathBuf,
+		/// Write all changes, e.g. when the last file got lost
+		#[clap(long)]
+		all: bool,
+	},
+	/// Apply the changes from a file that another copy exported, the latest change of a name wins on both sides
+	Import { path: PathBuf },
+	/// Give a database that was copied as a file its own identity, right after copying it
+	NewDeviceId,
+}
+
+#[derive(Debug, Parser)]
+enum GroupCommand {
+	/// Regroup all names that weren't grouped manually based on pronunciation and spelling
+	Auto,
+	/// Put names (and their existing variants) into the same group
+	Merge {
+		#[clap(required = true, num_args = 2..)]
+		names: Vec<String>,
+	},
+	/// Take a name out of its group
+	Split { name: String },
+	/// Show the spelling variants of a name
+	Show { name: String },
+	/// Set the preference of a name and all of its variants, no preference resets to undecided.
+	/// Favorites without a rating yet get the full 5 stars.
+	Prefer {
+		name: String,
+		preference: Option<NamePreference>,
+	},
+}
+
 impl Cli {
+	// one short match arm per command
+	#[expect(clippy::too_many_lines)]
 	pub fn run(self) -> anyhow::Result<()> {
 		use Command::*;
 
 		let runtime = runtime::Builder::new_current_thread().enable_all().build()?;
 		let database_pool = runtime.block_on(database::initialize(&self.database_path))?;
+		let project = || runtime.block_on(read_project(&self.project, &database_pool));
 
 		match self.command {
 			Parse { name_list } => {
@@ -56,22 +477,220 @@ impl Cli {
 				runtime.block_on(ingest(&name_list, database_pool.clone()))?;
 				runtime.block_on(database_pool.close());
 			}
-			ListAll { gender } => {
-				runtime.block_on(list_all(gender, database_pool.clone()))?;
+			ImportMetadata { metadata } => {
+				runtime.block_on(import_metadata(&metadata, database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
+			ImportNicknames { nicknames } => {
+				runtime.block_on(import_nicknames(&nicknames, database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
+			ListAll {
+				gender,
+				query,
+				structure,
+				exclude_similar_to_siblings,
+				due_for_review,
+			} => {
+				let preferences = if due_for_review {
+					PreferenceSelection::DUE_FOR_REVIEW
+				} else {
+					PreferenceSelection::ALL
+				};
+				runtime.block_on(list_all(
+					gender,
+					preferences,
+					&query.unwrap_or_default(),
+					&structure,
+					exclude_similar_to_siblings,
+					&project()?,
+					database_pool.clone(),
+				))?;
 				runtime.block_on(database_pool.close());
 			}
 			Random { gender } => {
 				runtime.block_on(random(gender, database_pool.clone()))?;
 				runtime.block_on(database_pool.close());
 			}
+			Show { name } => {
+				runtime.block_on(show(&name, &project()?, database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
+			Rate { name, stars } => {
+				runtime.block_on(rate(
+					&name,
+					stars,
+					self.voter.as_deref(),
+					&project()?,
+					database_pool.clone(),
+				))?;
+				runtime.block_on(database_pool.close());
+			}
+			Favorite { selection } => {
+				runtime.block_on(bulk_prefer(
+					Some(NamePreference::Favorite),
+					selection,
+					self.voter.as_deref(),
+					&project()?,
+					database_pool.clone(),
+				))?;
+				runtime.block_on(database_pool.close());
+			}
+			Nogo { selection } => {
+				runtime.block_on(bulk_prefer(
+					Some(NamePreference::NoGo),
+					selection,
+					self.voter.as_deref(),
+					&project()?,
+					database_pool.clone(),
+				))?;
+				runtime.block_on(database_pool.close());
+			}
+			Clear { selection } => {
+				runtime.block_on(bulk_prefer(
+					None,
+					selection,
+					self.voter.as_deref(),
+					&project()?,
+					database_pool.clone(),
+				))?;
+				runtime.block_on(database_pool.close());
+			}
+			SoundsLike { name } => {
+				runtime.block_on(sounds_like(name, &project()?, database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
+			Search { term, limit } => {
+				runtime.block_on(search(term, limit, &project()?, database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
+			Nicknames { name, language } => {
+				runtime.block_on(nicknames(&name, language, &project()?, database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
+			Similar { name, count } => {
+				runtime.block_on(similar(&name, count, database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
+			Surname { surname, clear } => {
+				runtime.block_on(configure_surname(surname, clear, project()?, database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
+			Check { name, language } => {
+				runtime.block_on(check(&name, language, &project()?, database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
+			Initials { command } => {
+				runtime.block_on(configure_initials(command, project()?, database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
+			Combinations { command } => {
+				runtime.block_on(combinations(command, &project()?, database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
+			Siblings { command } => {
+				runtime.block_on(siblings(command, &project()?, database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
+			Tags { command } => {
+				runtime.block_on(tags(command, &project()?, database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
+			Notes { command } => {
+				runtime.block_on(notes(
+					command,
+					self.voter.as_deref(),
+					&project()?,
+					database_pool.clone(),
+				))?;
+				runtime.block_on(database_pool.close());
+			}
+			Review { command } => {
+				runtime.block_on(review(command, &project()?, database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
+			History { command } => {
+				runtime.block_on(history(
+					command,
+					self.voter.as_deref(),
+					&project()?,
+					database_pool.clone(),
+				))?;
+				runtime.block_on(database_pool.close());
+			}
+			Group { command } => {
+				runtime.block_on(group(
+					command,
+					self.voter.as_deref(),
+					&project()?,
+					database_pool.clone(),
+				))?;
+				runtime.block_on(database_pool.close());
+			}
+			Projects { command } => {
+				runtime.block_on(projects(command, &self.project, database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
+			ExportPreferences { format, output } => {
+				runtime.block_on(export_preferences(
+					format,
+					output.as_deref(),
+					&project()?,
+					database_pool.clone(),
+				))?;
+				runtime.block_on(database_pool.close());
+			}
+			ImportPreferences { path, format, strategy } => {
+				runtime.block_on(import_preferences(
+					&path,
+					format.unwrap_or_else(|| ExchangeFormat::from_path(&path)),
+					strategy,
+					self.voter.as_deref(),
+					&project()?,
+					database_pool.clone(),
+				))?;
+				runtime.block_on(database_pool.close());
+			}
+			Merge {
+				other_database,
+				strategy,
+				other_voter,
+			} => {
+				runtime.block_on(merge(
+					&other_database,
+					strategy,
+					self.voter.as_deref(),
+					other_voter,
+					&project()?,
+					database_pool.clone(),
+				))?;
+				runtime.block_on(database_pool.close());
+			}
+			Sync { command } => {
+				runtime.block_on(sync(command, database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
+			Reindex => {
+				runtime.block_on(reindex(database_pool.clone()))?;
+				runtime.block_on(database_pool.close());
+			}
 			Gui => {
-				gui::start(runtime, &database_pool)?;
+				let project = project()?;
+				gui::start(runtime, &database_pool, project, self.voter)?;
 			}
 		}
 		Ok(())
 	}
 }
 
+const DEFAULT_PROJECT: &str = "default";
+
+async fn read_project(name: &str, database_pool: &SqlitePool) -> anyhow::Result<Project> {
+	database::read_project_by_name(name, database_pool)
+		.await?
+		.with_context(|| format!("Unknown project '{name}', create it with `projects add` first"))
+}
+
 pub async fn parse(name_list: &Path) -> anyhow::Result<()> {
 	stream_blocking_iterator(parse_csv(name_list)?)
 		.try_for_each(|line| {
@@ -86,26 +705,901 @@ pub async fn parse(name_list: &Path) -> anyhow::Result<()> {
 pub async fn ingest(name_list: &Path, database_pool: SqlitePool) -> anyhow::Result<()> {
 	let source = name_list.file_name().context("Missing filename")?.to_string_lossy();
 
-	stream_blocking_iterator(parse_csv(name_list)?)
-		.try_for_each(|record| {
-			let database_pool = &database_pool;
-			let source = &source;
-			async move {
-				database::upsert_name(&record.name, record.gender, database_pool).await?;
-				Ok(database::insert_name_record(&record, source, database_pool).await?)
-			}
-		})
+	// different spellings of a name in the same file are normalized to the same name, so their counts are added up
+	let counts = stream_blocking_iterator(parse_csv(name_list)?)
+		.try_fold(
+			HashMap::<_, u32>::new(),
+			|mut counts, NameRecord { name, count, gender }| {
+				*counts.entry((name, gender)).or_default() += count;
+				future::ready(Ok(counts))
+			},
+		)
 		.await?;
+
+	let mut names = HashSet::new();
+	for ((name, gender), count) in counts {
+		let record = NameRecord { name, count, gender };
+		database::upsert_name(&record.name, gender, &database_pool).await?;
+		database::insert_name_record(&record, &source, &database_pool).await?;
+		names.insert(record.name);
+	}
+
+	// the same name appears once per year and gender, its phonetic codes only need to be calculated once
+	for name in &names {
+		database::replace_phonetic_codes(name, &phonetic_codes(name), &database_pool).await?;
+	}
+	Ok(())
+}
+
+pub async fn import_metadata(metadata: &Path, database_pool: SqlitePool) -> anyhow::Result<()> {
+	let start = Instant::now();
+	let mut imported = 0;
+	let mut unknown_names = Vec::new();
+	for record in parse_metadata_csv(metadata)? {
+		let record = record?;
+		if database::replace_name_metadata(&record, &database_pool).await? {
+			imported += 1;
+		} else {
+			unknown_names.push(record.name);
+		}
+	}
+
+	println!(
+		"Imported metadata of {imported} names in {}",
+		PrettyPrintedDuration::from(start.elapsed())
+	);
+	if !unknown_names.is_empty() {
+		println!(
+			"Skipped names that aren't in the database: {}",
+			unknown_names.join(", ")
+		);
+	}
+	Ok(())
+}
+
+pub async fn import_nicknames(nicknames: &Path, database_pool: SqlitePool) -> anyhow::Result<()> {
+	let mut imported = 0;
+	let mut unknown_names = Vec::new();
+	for record in parse_nicknames_csv(nicknames)? {
+		let record = record?;
+		if database::insert_nickname(&record, &database_pool).await? {
+			imported += 1;
+		} else if !unknown_names.contains(&record.name) {
+			unknown_names.push(record.name);
+		}
+	}
+
+	println!("Imported {imported} nicknames");
+	if !unknown_names.is_empty() {
+		println!(
+			"Skipped names that aren't in the database: {}",
+			unknown_names.join(", ")
+		);
+	}
+	Ok(())
+}
+
+pub async fn export_preferences(
+	format: ExchangeFormat,
+	output: Option<&Path>,
+	project: &Project,
+	database_pool: SqlitePool,
+) -> anyhow::Result<()> {
+	let mut records = BTreeMap::new();
+	for name_rating in database::views::read_name_ratings(project.id, &database_pool).await? {
+		records.insert(
+			name_rating.name.clone(),
+			PreferenceRecord {
+				name: name_rating.name,
+				preference: Some(name_rating.preference),
+				stars: name_rating.stars,
+				notes: Vec::new(),
+			},
+		);
+	}
+	for name in database::read_names_with_notes(project.id, &database_pool).await? {
+		let notes = database::views::read_name_notes(&name, project.id, &database_pool).await?;
+		records
+			.entry(name.clone())
+			.or_insert_with(|| PreferenceRecord {
+				name,
+				preference: None,
+				stars: None,
+				notes: Vec::new(),
+			})
+			.notes = notes
+			.into_iter()
+			.map(|note| NoteRecord::new(note.voter, note.note, note.created_at))
+			.collect();
+	}
+	let records = records.into_values().collect::<Vec<_>>();
+
+	match output {
+		Some(output) => {
+			preference_exchange::write_records(&records, format, File::create(output)?)?;
+			println!("Exported {} names to {}", records.len(), output.display());
+		}
+		None => preference_exchange::write_records(&records, format, std::io::stdout().lock())?,
+	}
+	Ok(())
+}
+
+pub async fn import_preferences(
+	path: &Path,
+	format: ExchangeFormat,
+	strategy: ImportStrategy,
+	voter: Option<&str>,
+	project: &Project,
+	database_pool: SqlitePool,
+) -> anyhow::Result<()> {
+	let start = Instant::now();
+	let current_ratings = database::views::read_name_ratings(project.id, &database_pool)
+		.await?
+		.into_iter()
+		.map(|name_rating| (name_rating.name.clone(), name_rating.rating()))
+		.collect::<HashMap<_, _>>();
+
+	// everything is checked before anything is written, so a broken file doesn't get imported halfway
+	let mut ratings = Vec::new();
+	let mut notes = Vec::new();
+	let mut kept = 0;
+	let mut unknown_names = Vec::new();
+	for record in preference_exchange::read_records(path, format)? {
+		let name = normalize_name(&record.name);
+		if !database::name_exists(&name, &database_pool).await? {
+			unknown_names.push(record.name);
+			continue;
+		}
+
+		let decided = current_ratings.contains_key(&name);
+		let rating = record.rating();
+		match strategy {
+			ImportStrategy::Overwrite => ratings.push((name.clone(), rating)),
+			ImportStrategy::KeepExisting | ImportStrategy::OnlyUndecided if decided => {
+				kept += 1;
+				if strategy == ImportStrategy::OnlyUndecided {
+					continue;
+				}
+			}
+			ImportStrategy::KeepExisting | ImportStrategy::OnlyUndecided => {
+				if rating.preference.is_some() {
+					ratings.push((name.clone(), rating));
+				}
+			}
+		}
+		for note in record.notes {
+			notes.push(ImportedNote {
+				name: name.clone(),
+				created_at: note.created_at()?,
+				voter: note.voter.or_else(|| voter.map(ToOwned::to_owned)),
+				note: note.note,
+			});
+		}
+	}
+
+	let (changes, imported_notes) =
+		database::import_preferences(&ratings, &notes, voter, project.id, &database_pool).await?;
+
+	println!(
+		"Changed the preferences of {} names and imported {imported_notes} notes in {}",
+		changes.len(),
+		PrettyPrintedDuration::from(start.elapsed())
+	);
+	if kept > 0 {
+		println!("Kept the preferences of {kept} names that were already decided");
+	}
+	if !unknown_names.is_empty() {
+		println!(
+			"Skipped names that aren't in the database: {}",
+			unknown_names.join(", ")
+		);
+	}
 	Ok(())
 }
 
-pub async fn list_all(gender: Gender, database_pool: SqlitePool) -> anyhow::Result<()> {
-	database::list_all(gender, &database_pool)
-		.try_for_each(|name| {
+pub async fn merge(
+	other_database: &Path,
+	strategy: MergeStrategy,
+	voter: Option<&str>,
+	other_voter: Option<String>,
+	project: &Project,
+	database_pool: SqlitePool,
+) -> anyhow::Result<()> {
+	// attaching a file that doesn't exist would silently create an empty database there
+	anyhow::ensure!(
+		other_database.is_file(),
+		"There is no database at {}",
+		other_database.display()
+	);
+	anyhow::ensure!(
+		strategy != MergeStrategy::SeparateVoters || voter.is_some(),
+		"Merging with {strategy} needs `--voter` for the ratings of this database"
+	);
+	let other_voter = match other_voter {
+		Some(other_voter) => other_voter,
+		None => other_database
+			.file_stem()
+			.context("Missing filename")?
+			.to_string_lossy()
+			.into_owned(),
+	};
+	let their_ratings = database::read_other_database_ratings(other_database, &project.name, &database_pool)
+		.await
+		.with_context(|| {
+			format!(
+				"Failed to read {}, open it with this version once to update it",
+				other_database.display()
+			)
+		})?
+		.with_context(|| format!("There is no project '{}' in {}", project.name, other_database.display()))?;
+	let our_ratings = database::views::read_name_ratings(project.id, &database_pool)
+		.await?
+		.into_iter()
+		.map(|name_rating| (name_rating.name.clone(), name_rating.rating()))
+		.collect::<HashMap<_, _>>();
+
+	let mut ratings = Vec::new();
+	let mut known_their_ratings = Vec::new();
+	let mut conflicts = 0;
+	let mut unknown_names = Vec::new();
+	for (name, theirs) in their_ratings {
+		if !database::name_exists(&name, &database_pool).await? {
+			unknown_names.push(name);
+			continue;
+		}
+
+		known_their_ratings.push((name.clone(), theirs));
+		match our_ratings.get(&name).copied() {
+			None => ratings.push((name, theirs)),
+			Some(ours) if ours == theirs => {}
+			Some(ours) => {
+				let resolved = strategy.resolve(ours, theirs);
+				println!("{name}: ours {ours}, theirs {theirs} → {resolved}");
+				ratings.push((name, resolved));
+				conflicts += 1;
+			}
+		}
+	}
+
+	let changes = database::set_name_ratings(&ratings, Some(&other_voter), project.id, &database_pool).await?;
+	if let (MergeStrategy::SeparateVoters, Some(voter)) = (strategy, voter) {
+		let our_ratings = our_ratings.into_iter().collect::<Vec<_>>();
+		database::set_voter_ratings(voter, &our_ratings, project.id, &database_pool).await?;
+		database::set_voter_ratings(&other_voter, &known_their_ratings, project.id, &database_pool).await?;
+		println!("Kept the ratings of {voter} and {other_voter} separately, `show` lists them");
+	}
+
+	println!(
+		"Resolved {conflicts} conflicts with {strategy}, changed the preferences of {} names",
+		changes.len()
+	);
+	if !unknown_names.is_empty() {
+		println!(
+			"Skipped names that aren't in the database: {}",
+			unknown_names.join(", ")
+		);
+	}
+	Ok(())
+}
+
+pub async fn list_all(
+	gender: Gender,
+	preferences: PreferenceSelection,
+	query: &SearchQuery,
+	structure: &StructureFilter,
+	exclude_similar_to_siblings: bool,
+	project: &Project,
+	database_pool: SqlitePool,
+) -> anyhow::Result<()> {
+	let search = NameSearch::Query(query.clone());
+	let names = database::views::read_all_names(
+		gender,
+		preferences,
+		&search,
+		structure,
+		exclude_similar_to_siblings,
+		None,
+		project.id,
+		&database_pool,
+	)
+	.await?;
+
+	let blocklist = database::read_initials_blocklist(&database_pool).await?;
+	for name in names {
+		let flags = project
+			.surname
+			.as_deref()
+			.map(|surname| blocklist.flags(&name.name, project.middle_name.as_deref(), surname))
+			.unwrap_or_default();
+		if flags.is_empty() {
 			println!("{name:?}");
-			std::future::ready(Ok(()))
-		})
+		} else {
+			println!("{name:?} [blocked initials: {}]", flags.join(", "));
+		}
+	}
+	Ok(())
+}
+
+pub async fn sounds_like(name: String, project: &Project, database_pool: SqlitePool) -> anyhow::Result<()> {
+	let search = NameSearch::SoundsLike(normalize_name(&name));
+	let names = database::views::read_all_names(
+		Gender::Both,
+		PreferenceSelection::ALL,
+		&search,
+		&StructureFilter::default(),
+		false,
+		None,
+		project.id,
+		&database_pool,
+	)
+	.await?;
+	for name in names {
+		println!("{name:?}");
+	}
+	Ok(())
+}
+
+pub async fn search(term: String, limit: usize, project: &Project, database_pool: SqlitePool) -> anyhow::Result<()> {
+	let search = NameSearch::Fuzzy(term.clone());
+	let names = database::views::read_all_names(
+		Gender::Both,
+		PreferenceSelection::ALL,
+		&search,
+		&StructureFilter::default(),
+		false,
+		None,
+		project.id,
+		&database_pool,
+	)
+	.await?;
+	for name in names.into_iter().take(limit) {
+		println!("{} (edit distance {})", name.name, levenshtein(&term, &name.name));
+	}
+	Ok(())
+}
+
+pub async fn nicknames(
+	name: &str,
+	language: Language,
+	project: &Project,
+	database_pool: SqlitePool,
+) -> anyhow::Result<()> {
+	let name = normalize_name(name);
+	for nickname in database::views::read_nicknames(&name, language, project.id, &database_pool).await? {
+		let derived = if nickname.derived { " (derived)" } else { "" };
+		let no_go = if nickname.no_go {
+			" [warning: on the no-go list]"
+		} else {
+			""
+		};
+		println!("{}{derived}{no_go}", nickname.name);
+	}
+	Ok(())
+}
+
+pub async fn similar(name: &str, count: usize, database_pool: SqlitePool) -> anyhow::Result<()> {
+	let name = normalize_name(name);
+	for similar_name in database::views::read_similar_names(&name, count, &database_pool).await? {
+		println!(
+			"{} (edit distance {}, longest common substring {})",
+			similar_name.name, similar_name.similarity.levenshtein, similar_name.similarity.longest_common_substring,
+		);
+	}
+	Ok(())
+}
+
+pub async fn configure_surname(
+	surname: Option<String>,
+	clear: bool,
+	mut project: Project,
+	database_pool: SqlitePool,
+) -> anyhow::Result<()> {
+	if clear {
+		project.surname = None;
+		database::update_project(&project, &database_pool).await?;
+	} else if let Some(surname) = surname {
+		project.surname = Some(normalize_name(&surname));
+		database::update_project(&project, &database_pool).await?;
+	}
+
+	match project.surname {
+		Some(surname) => println!("Surname: {surname}"),
+		None => println!("No surname configured"),
+	}
+	Ok(())
+}
+
+pub async fn check(name: &str, language: Language, project: &Project, database_pool: SqlitePool) -> anyhow::Result<()> {
+	let surname = project
+		.surname
+		.as_deref()
+		.context("No surname configured, set it with the `surname` command first")?;
+	let name = normalize_name(name);
+	let compatibility = SurnameCompatibility::between(&name, surname, language);
+	println!(
+		"{name} {surname}: {}/100 ({} + {} syllables)",
+		compatibility.score(),
+		compatibility.first_name_syllables,
+		compatibility.surname_syllables,
+	);
+	for issue in compatibility.issues() {
+		println!("- {issue}");
+	}
+
+	let blocklist = database::read_initials_blocklist(&database_pool).await?;
+	for flag in blocklist.flags(&name, project.middle_name.as_deref(), surname) {
+		println!("- Blocked initials: {flag}");
+	}
+	Ok(())
+}
+
+async fn configure_initials(
+	command: InitialsCommand,
+	mut project: Project,
+	database_pool: SqlitePool,
+) -> anyhow::Result<()> {
+	use InitialsCommand::*;
+
+	match command {
+		MiddleName { middle_name, clear } => {
+			if clear {
+				project.middle_name = None;
+				database::update_project(&project, &database_pool).await?;
+			} else if let Some(middle_name) = middle_name {
+				project.middle_name = Some(normalize_name(&middle_name));
+				database::update_project(&project, &database_pool).await?;
+			}
+
+			match project.middle_name {
+				Some(middle_name) => println!("Middle name: {middle_name}"),
+				None => println!("No middle name configured"),
+			}
+		}
+		Block { initials } => {
+			let initials = normalize_initials(&initials);
+			anyhow::ensure!(!initials.is_empty(), "Initials need to contain at least one letter");
+			database::insert_blocked_initials(&initials, &database_pool).await?;
+		}
+		Unblock { initials } => {
+			let initials = normalize_initials(&initials);
+			if InitialsBlocklist::bundled().contains(&initials) {
+				println!("{initials} is part of the bundled blocklist and stays blocked");
+			}
+			database::delete_blocked_initials(&initials, &database_pool).await?;
+		}
+		List => {
+			let mut bundled = InitialsBlocklist::bundled().iter().collect::<Vec<_>>();
+			bundled.sort_unstable();
+			println!("Bundled: {}", bundled.join(", "));
+			println!(
+				"Added: {}",
+				database::read_blocked_initials(&database_pool).await?.join(", ")
+			);
+		}
+	}
+	Ok(())
+}
+
+async fn combinations(
+	command: CombinationsCommand,
+	project: &Project,
+	database_pool: SqlitePool,
+) -> anyhow::Result<()> {
+	use CombinationsCommand::*;
+
+	match command {
+		List { hyphenated, language } => {
+			let mut combinations = database::views::read_combinations(hyphenated, project.id, &database_pool)
+				.await?
+				.into_iter()
+				.map(|combination| {
+					let analysis =
+						CombinationAnalysis::new(&combination.combination, project.surname.as_deref(), language);
+					(combination, analysis)
+				})
+				.collect::<Vec<_>>();
+			combinations.sort_by_key(|(_, analysis)| Reverse(analysis.score()));
+
+			for (combination, analysis) in combinations {
+				let preference = combination
+					.preference
+					.map_or_else(String::new, |preference| format!(" [{}]", preference.as_ref()));
+				println!("{}/100 {}{preference}", analysis.score(), combination.combination);
+				for issue in analysis.issues() {
+					println!("  - {issue}");
+				}
+			}
+		}
+		Rate {
+			first_name,
+			middle_name,
+			hyphenated,
+			preference,
+		} => {
+			let combination = Combination {
+				first_name: normalize_name(&first_name),
+				middle_name: normalize_name(&middle_name),
+				hyphenated,
+			};
+			match preference {
+				Some(preference) => {
+					database::upsert_combination_preference(&combination, preference, project.id, &database_pool)
+						.await?;
+				}
+				None => database::delete_combination_preference(&combination, project.id, &database_pool).await?,
+			}
+		}
+	}
+	Ok(())
+}
+
+async fn siblings(command: SiblingsCommand, project: &Project, database_pool: SqlitePool) -> anyhow::Result<()> {
+	use SiblingsCommand::*;
+
+	match command {
+		Add { name } => database::insert_sibling(&normalize_name(&name), project.id, &database_pool).await?,
+		Remove { name } => database::delete_sibling(&normalize_name(&name), project.id, &database_pool).await?,
+		List => {
+			for sibling in database::read_siblings(project.id, &database_pool).await? {
+				println!("{sibling}");
+			}
+		}
+		Check { name, language } => {
+			let siblings = database::read_siblings(project.id, &database_pool).await?;
+			anyhow::ensure!(
+				!siblings.is_empty(),
+				"No siblings configured, add them with `siblings add` first"
+			);
+
+			let name = normalize_name(&name);
+			let era = database::views::read_popularity_era(&name, &database_pool).await?;
+			let codes = stored_phonetic_codes(&name, &database_pool).await?;
+			for sibling in siblings {
+				let sibling_era = database::views::read_popularity_era(&sibling, &database_pool).await?;
+				let sibling_codes = stored_phonetic_codes(&sibling, &database_pool).await?;
+				let harmony =
+					SiblingHarmony::between(&name, &codes, &sibling, &sibling_codes, era, sibling_era, language);
+				println!("{name} and {sibling}: {}/100", harmony.score());
+				for issue in harmony.issues() {
+					println!("- {issue}");
+				}
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Siblings and names that are checked don't have to be part of the datasets, their codes are calculated instead
+async fn stored_phonetic_codes(name: &str, database_pool: &SqlitePool) -> anyhow::Result<Vec<PhoneticCode>> {
+	if database::name_exists(name, database_pool).await? {
+		Ok(database::views::read_phonetic_codes(name, database_pool).await?)
+	} else {
+		Ok(phonetic_codes(name))
+	}
+}
+
+async fn tags(command: TagsCommand, project: &Project, database_pool: SqlitePool) -> anyhow::Result<()> {
+	use TagsCommand::*;
+
+	match command {
+		Add { tag, names, query } => {
+			let names = names_to_tag(names, query, project, &database_pool).await?;
+			let tagged = database::tag_names(tag.trim(), &names, project.id, &database_pool).await?;
+			println!("Tagged {tagged} names with '{tag}'");
+		}
+		Remove { tag, names, query } => {
+			let names = names_to_tag(names, query, project, &database_pool).await?;
+			let untagged = database::untag_names(tag.trim(), &names, project.id, &database_pool).await?;
+			println!("Removed '{tag}' from {untagged} names");
+		}
+		List => {
+			for tag in database::read_tags(project.id, &database_pool).await? {
+				println!("{tag}");
+			}
+		}
+		Delete { tag } => {
+			anyhow::ensure!(
+				database::delete_tag(tag.trim(), project.id, &database_pool).await?,
+				"There is no tag '{tag}' in project '{}'",
+				project.name
+			);
+		}
+	}
+	Ok(())
+}
+
+/// The given names together with all names matching the query
+async fn names_to_tag(
+	names: Vec<String>,
+	query: Option<SearchQuery>,
+	project: &Project,
+	database_pool: &SqlitePool,
+) -> anyhow::Result<Vec<String>> {
+	anyhow::ensure!(
+		!names.is_empty() || query.is_some(),
+		"Either pass names or a search query with --query"
+	);
+
+	let mut names = names.iter().map(|name| normalize_name(name)).collect::<Vec<_>>();
+	if let Some(query) = query {
+		let matching_names = database::views::read_all_names(
+			Gender::Both,
+			PreferenceSelection::ALL,
+			&NameSearch::Query(query),
+			&StructureFilter::default(),
+			false,
+			None,
+			project.id,
+			database_pool,
+		)
 		.await?;
+		names.extend(matching_names.into_iter().map(|name| name.name));
+	}
+	Ok(names)
+}
+
+async fn notes(
+	command: NotesCommand,
+	voter: Option<&str>,
+	project: &Project,
+	database_pool: SqlitePool,
+) -> anyhow::Result<()> {
+	use NotesCommand::*;
+
+	match command {
+		Add { name, note } => {
+			let name = normalize_name(&name);
+			database::views::read_one(&name, project.id, &database_pool)
+				.await
+				.with_context(|| format!("Unknown name '{name}'"))?;
+			database::insert_name_note(&name, voter, &note, project.id, &database_pool).await?;
+		}
+		List { name } => {
+			let names = match name {
+				Some(name) => vec![normalize_name(&name)],
+				None => database::read_names_with_notes(project.id, &database_pool).await?,
+			};
+			for name in names {
+				println!("{name}");
+				for note in database::views::read_name_notes(&name, project.id, &database_pool).await? {
+					println!("  [{}] {note}", note.id);
+				}
+			}
+		}
+		Delete { id } => {
+			anyhow::ensure!(
+				database::delete_name_note(id, project.id, &database_pool).await?,
+				"There is no note with id {id} in project '{}'",
+				project.name
+			);
+		}
+	}
+	Ok(())
+}
+
+async fn review(command: ReviewCommand, project: &Project, database_pool: SqlitePool) -> anyhow::Result<()> {
+	use ReviewCommand::*;
+
+	let (name, reviewed) = match command {
+		Confirm { name } => {
+			let name = normalize_name(&name);
+			let confirmed = database::confirm_favorite(&name, project.id, &database_pool).await?;
+			(name, confirmed)
+		}
+		Hesitate { name } => {
+			let name = normalize_name(&name);
+			let hesitated = database::hesitate_on_favorite(&name, project.id, &database_pool).await?;
+			(name, hesitated)
+		}
+	};
+	anyhow::ensure!(reviewed, "'{name}' isn't a favorite in project '{}'", project.name);
+	Ok(())
+}
+
+async fn history(
+	command: HistoryCommand,
+	voter: Option<&str>,
+	project: &Project,
+	database_pool: SqlitePool,
+) -> anyhow::Result<()> {
+	use HistoryCommand::*;
+
+	match command {
+		List { name } => {
+			let name = name.map(|name| normalize_name(&name));
+			for change in database::views::read_preference_history(name.as_deref(), project.id, &database_pool).await? {
+				println!("[{}] {} {change}", change.id, change.name);
+			}
+		}
+		Restore { id } => {
+			let change = database::views::read_preference_change(id, project.id, &database_pool)
+				.await?
+				.with_context(|| {
+					format!(
+						"There is no preference change with id {id} in project '{}'",
+						project.name
+					)
+				})?;
+			let rating = change.new_rating();
+			database::set_name_rating(&change.name, rating, voter, project.id, &database_pool).await?;
+			println!("{}: {rating}", change.name);
+		}
+	}
+	Ok(())
+}
+
+async fn sync(command: SyncCommand, database_pool: SqlitePool) -> anyhow::Result<()> {
+	use SyncCommand::*;
+
+	match command {
+		Export { path, all } => {
+			let device_id = database::read_sync_device_id(&database_pool).await?;
+			let (operations, last_operation) = database::read_sync_operations(all, &database_pool).await?;
+			let exported = operations.len();
+			sync::write_changeset(&Changeset { device_id, operations }, &path)?;
+			database::mark_sync_exported(last_operation, &database_pool).await?;
+			println!("Exported {exported} changes to {}", path.display());
+		}
+		Import { path } => {
+			let changeset = sync::read_changeset(&path)?;
+			anyhow::ensure!(
+				changeset.device_id != database::read_sync_device_id(&database_pool).await?,
+				"{} was exported by this database or by a copy of its file, run `sync new-device-id` in the copy",
+				path.display()
+			);
+
+			let import = database::import_sync_operations(changeset.operations, &database_pool).await?;
+			for (project, change) in &import.changes {
+				println!("{project}: {}: {} → {}", change.name, change.before, change.after);
+			}
+			println!(
+				"Imported {} new changes from {}, {} of them were overridden by later changes",
+				import.new_operations, changeset.device_id, import.superseded_operations
+			);
+			if !import.unknown_projects.is_empty() {
+				println!(
+					"Skipped projects that aren't in the database: {}",
+					import.unknown_projects.join(", ")
+				);
+			}
+			if !import.unknown_names.is_empty() {
+				println!(
+					"Skipped names that aren't in the database: {}",
+					import.unknown_names.join(", ")
+				);
+			}
+		}
+		NewDeviceId => {
+			let device_id = database::renew_sync_device_id(&database_pool).await?;
+			println!("This database is now synced as {device_id}");
+		}
+	}
+	Ok(())
+}
+
+async fn group(
+	command: GroupCommand,
+	voter: Option<&str>,
+	project: &Project,
+	database_pool: SqlitePool,
+) -> anyhow::Result<()> {
+	use GroupCommand::*;
+
+	match command {
+		Auto => {
+			let start = Instant::now();
+			let names = database::list_all(Gender::Both, &database_pool)
+				.map_ok(|Name { name, .. }| name)
+				.try_collect::<Vec<_>>()
+				.await?;
+			let groups = name_groups::cluster_variants(&names);
+			database::replace_automatic_name_groups(&groups, &database_pool).await?;
+			println!(
+				"Found {} groups of spelling variants in {}",
+				groups.len(),
+				PrettyPrintedDuration::from(start.elapsed())
+			);
+		}
+		Merge { names } => {
+			let names = names.iter().map(|name| normalize_name(name)).collect::<Vec<_>>();
+			database::merge_name_groups(&names, &database_pool).await?;
+		}
+		Split { name } => database::split_name_from_group(&normalize_name(&name), &database_pool).await?,
+		Show { name } => println!(
+			"{}",
+			database::views::read_name_group(&normalize_name(&name), &database_pool)
+				.await?
+				.join(", ")
+		),
+		Prefer { name, preference } => {
+			let name = normalize_name(&name);
+			let rating = database::views::read_one(&name, project.id, &database_pool)
+				.await
+				.with_context(|| format!("Unknown name '{name}'"))?
+				.rating()
+				.with_preference(preference);
+			for change in database::set_group_preference(&name, rating, voter, project.id, &database_pool).await? {
+				println!("{}: {} → {}", change.name, change.before, change.after);
+			}
+		}
+	}
+	Ok(())
+}
+
+async fn projects(command: ProjectsCommand, current_project: &str, database_pool: SqlitePool) -> anyhow::Result<()> {
+	use ProjectsCommand::*;
+
+	match command {
+		List => {
+			let today = Local::now().date_naive();
+			for project in database::read_projects(&database_pool).await? {
+				let marker = if project.name == current_project { "*" } else { " " };
+				let surname = project.surname.as_deref().unwrap_or("no surname");
+				let due_date = project.due_date.map_or_else(String::new, |due_date| {
+					format!(", due {due_date} (in {} days)", (due_date - today).num_days())
+				});
+				println!("{marker} {} ({}, {surname}{due_date})", project.name, project.gender);
+			}
+		}
+		Add {
+			name,
+			surname,
+			gender,
+			due_date,
+		} => {
+			let id = database::insert_project(&name, &database_pool).await?;
+			let project = Project {
+				id,
+				name,
+				surname: surname.as_deref().map(normalize_name),
+				middle_name: None,
+				gender,
+				due_date,
+			};
+			database::update_project(&project, &database_pool).await?;
+		}
+		Update {
+			name,
+			gender,
+			due_date,
+			clear_due_date,
+		} => {
+			let mut project = read_project(&name, &database_pool).await?;
+			if let Some(gender) = gender {
+				project.gender = gender;
+			}
+			if clear_due_date {
+				project.due_date = None;
+			} else if due_date.is_some() {
+				project.due_date = due_date;
+			}
+			database::update_project(&project, &database_pool).await?;
+		}
+		Remove { name } => {
+			let project = read_project(&name, &database_pool).await?;
+			anyhow::ensure!(
+				database::read_projects(&database_pool).await?.len() > 1,
+				"Can't remove the last project"
+			);
+			database::delete_project(project.id, &database_pool).await?;
+		}
+	}
+	Ok(())
+}
+
+pub async fn reindex(database_pool: SqlitePool) -> anyhow::Result<()> {
+	let start = Instant::now();
+	let names = database::list_all(Gender::Both, &database_pool)
+		.try_collect::<Vec<_>>()
+		.await?;
+	for Name { name, .. } in &names {
+		database::replace_phonetic_codes(name, &phonetic_codes(name), &database_pool).await?;
+	}
+	println!(
+		"Reindexed {} names in {}",
+		names.len(),
+		PrettyPrintedDuration::from(start.elapsed())
+	);
 	Ok(())
 }
 
@@ -114,3 +1608,214 @@ pub async fn random(gender: Gender, database_pool: SqlitePool) -> anyhow::Result
 	println!("{name:?}");
 	Ok(())
 }
+
+pub async fn rate(
+	name: &str,
+	stars: u8,
+	voter: Option<&str>,
+	project: &Project,
+	database_pool: SqlitePool,
+) -> anyhow::Result<()> {
+	let name = normalize_name(name);
+	let current_rating = database::views::read_one(&name, project.id, &database_pool)
+		.await
+		.with_context(|| format!("Unknown name '{name}'"))?
+		.rating();
+	// like in the GUI, rating doesn't lift a veto, the stars are kept for when it is
+	let preference = match current_rating.preference {
+		Some(NamePreference::NoGo) => NamePreference::NoGo,
+		Some(NamePreference::Favorite) | None => NamePreference::Favorite,
+	};
+	let rating = Rating {
+		preference: Some(preference),
+		stars: Some(stars),
+	};
+	database::set_name_rating(&name, rating, voter, project.id, &database_pool).await?;
+	Ok(())
+}
+
+async fn bulk_prefer(
+	preference: Option<NamePreference>,
+	selection: BulkSelection,
+	voter: Option<&str>,
+	project: &Project,
+	database_pool: SqlitePool,
+) -> anyhow::Result<()> {
+	let current_ratings = select_names(&selection, project, &database_pool).await?;
+	let ratings = current_ratings
+		.into_iter()
+		.filter(|(_, current)| !selection.undecided_only || current.preference.is_none())
+		.map(|(name, current)| (name, current, current.with_preference(preference)))
+		.filter(|(_, current, rating)| current != rating)
+		.collect::<Vec<_>>();
+	if ratings.is_empty() {
+		println!("Nothing to change");
+		return Ok(());
+	}
+
+	// without stars, it's displayed as just the preference
+	let target = Rating {
+		preference,
+		stars: None,
+	};
+	if selection.dry_run {
+		for (name, current, rating) in &ratings {
+			println!("{name}: {current} → {rating}");
+		}
+		println!("Would change {} names to {target}", ratings.len());
+		return Ok(());
+	}
+	if !selection.yes {
+		print!("Change {} names to {target}? [y/N] ", ratings.len());
+		std::io::stdout().flush()?;
+		let mut answer = String::new();
+		std::io::stdin().read_line(&mut answer)?;
+		if !answer.trim().eq_ignore_ascii_case("y") {
+			println!("Nothing changed");
+			return Ok(());
+		}
+	}
+
+	let ratings = ratings
+		.into_iter()
+		.map(|(name, _, rating)| (name, rating))
+		.collect::<Vec<_>>();
+	let changes = database::set_name_ratings(&ratings, voter, project.id, &database_pool).await?;
+	println!("Changed {} names", changes.len());
+	Ok(())
+}
+
+/// The current ratings of the selected names
+async fn select_names(
+	selection: &BulkSelection,
+	project: &Project,
+	database_pool: &SqlitePool,
+) -> anyhow::Result<BTreeMap<String, Rating>> {
+	let mut names = selection.names.clone();
+	if let Some(file) = &selection.file {
+		let content = std::fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?;
+		names.extend(
+			content
+				.lines()
+				.map(str::trim)
+				.filter(|name| !name.is_empty())
+				.map(str::to_owned),
+		);
+	}
+
+	let mut current_ratings = BTreeMap::new();
+	let mut unknown_names = Vec::new();
+	for name in names {
+		let normalized_name = normalize_name(&name);
+		match database::views::read_one(&normalized_name, project.id, database_pool).await {
+			Ok(name_with_preference) => {
+				current_ratings.insert(normalized_name, name_with_preference.rating());
+			}
+			Err(sqlx::Error::RowNotFound) => unknown_names.push(name),
+			Err(error) => return Err(error.into()),
+		}
+	}
+
+	let mut query = SearchQuery::default();
+	for predicate in [
+		selection.contains.clone().map(Predicate::NameContains),
+		selection.ends_with.clone().map(Predicate::EndsWith),
+	]
+	.into_iter()
+	.flatten()
+	{
+		query.terms.push(Term {
+			negated: false,
+			predicate,
+		});
+	}
+	if selection.gender.is_some() || !query.terms.is_empty() {
+		let preferences = if selection.undecided_only {
+			PreferenceSelection::UNDECIDED
+		} else {
+			PreferenceSelection::ALL
+		};
+		let filtered_names = database::views::read_all_names(
+			selection.gender.unwrap_or(Gender::Both),
+			preferences,
+			&NameSearch::Query(query),
+			&StructureFilter::default(),
+			false,
+			None,
+			project.id,
+			database_pool,
+		)
+		.await?;
+		for name in filtered_names {
+			current_ratings.insert(name.name.clone(), name.rating());
+		}
+	}
+	anyhow::ensure!(
+		!current_ratings.is_empty() || !unknown_names.is_empty(),
+		"No names selected, pass names, a `--file` or a filter like `--contains`"
+	);
+	if !unknown_names.is_empty() {
+		println!(
+			"Skipped names that aren't in the database: {}",
+			unknown_names.join(", ")
+		);
+	}
+	Ok(current_ratings)
+}
+
+pub async fn show(name: &str, project: &Project, database_pool: SqlitePool) -> anyhow::Result<()> {
+	let name = normalize_name(name);
+	let name_with_preference = database::views::read_one(&name, project.id, &database_pool)
+		.await
+		.with_context(|| format!("Unknown name '{name}'"))?;
+	println!("{} ({})", name_with_preference.name, name_with_preference.gender);
+	if let Some(preference) = name_with_preference.preference {
+		println!("Preference: {}", preference.as_ref());
+	}
+	if let Some(stars) = name_with_preference.stars {
+		println!("Rating: {stars}/{MAXIMUM_STARS} stars");
+	}
+	for voter_rating in database::views::read_voter_ratings(&name, project.id, &database_pool).await? {
+		println!("Rated by {}: {}", voter_rating.voter, voter_rating.rating());
+	}
+
+	let metadata = database::views::read_name_metadata(&name, &database_pool).await?;
+	if let Some(meaning) = metadata.meaning {
+		println!("Meaning: {meaning}");
+	}
+	if let Some(origin) = metadata.origin {
+		println!("Origin: {origin}");
+	}
+	if !metadata.name_days.is_empty() {
+		println!(
+			"Name days: {}",
+			metadata
+				.name_days
+				.iter()
+				.map(ToString::to_string)
+				.collect::<Vec<_>>()
+				.join(", ")
+		);
+	}
+
+	let mut statistics = database::views::read_name_statistics(&name, &database_pool).await?;
+	statistics.sort_by_key(|statistics| year_of_source(&statistics.source));
+	for statistics in statistics {
+		let year = year_of_source(&statistics.source).map_or_else(String::new, |year| format!(" ({year})"));
+		let share_within_gender = BirthShare::new(statistics.count, statistics.gender_total).unwrap_or_default();
+		let share = BirthShare::new(statistics.count, statistics.source_total).unwrap_or_default();
+		println!(
+			"{source}{year}: {count} {gender} births ({percent:.3}% of all {gender} births), {share}",
+			source = statistics.source,
+			count = statistics.count,
+			gender = statistics.gender,
+			percent = share_within_gender.percent(),
+		);
+	}
+
+	if let Some(birth_share) = name_with_preference.birth_share {
+		println!("Averaged over all sources: {birth_share}");
+	}
+
+	Ok(())
+}
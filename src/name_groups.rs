@@ -0,0 +1,73 @@
+use crate::phonetics::cologne_code;
+use crate::similarity::levenshtein;
+use std::collections::HashMap;
+
+/// Clusters names that are probably spelling variants of each other, like "Maria" and "Marie".
+///
+/// Names are variants if they are pronounced the same according to Kölner Phonetik and are only a few edits apart.
+/// Only groups with at least two names are returned.
+pub fn cluster_variants(names: &[String]) -> Vec<Vec<String>> {
+	let mut buckets = HashMap::<String, Vec<usize>>::new();
+	for (index, name) in names.iter().enumerate() {
+		let code = cologne_code(name);
+		if !code.is_empty() {
+			buckets.entry(code).or_default().push(index);
+		}
+	}
+
+	let mut clusters = UnionFind::new(names.len());
+	for bucket in buckets.values() {
+		for (position, &a) in bucket.iter().enumerate() {
+			for &b in &bucket[position + 1..] {
+				if are_variants(&names[a], &names[b]) {
+					clusters.union(a, b);
+				}
+			}
+		}
+	}
+
+	let mut groups = HashMap::<usize, Vec<String>>::new();
+	for (index, name) in names.iter().enumerate() {
+		groups.entry(clusters.find(index)).or_default().push(name.clone());
+	}
+
+	let mut groups = groups.into_values().filter(|group| group.len() > 1).collect::<Vec<_>>();
+	groups.sort();
+	groups
+}
+
+pub fn are_variants(a: &str, b: &str) -> bool {
+	// short names quickly become different names with only two edits, e.g. "Mia" and "Maja"
+	let shortest_length = a.chars().count().min(b.chars().count());
+	let maximum_distance = if shortest_length <= 4 { 1 } else { 2 };
+	levenshtein(a, b) <= maximum_distance
+}
+
+struct UnionFind {
+	parents: Vec<usize>,
+}
+
+impl UnionFind {
+	fn new(size: usize) -> Self {
+		Self {
+			parents: (0..size).collect(),
+		}
+	}
+
+	fn find(&mut self, index: usize) -> usize {
+		let parent = self.parents[index];
+		if parent == index {
+			return index;
+		}
+
+		let root = self.find(parent);
+		self.parents[index] = root;
+		root
+	}
+
+	fn union(&mut self, a: usize, b: usize) {
+		let a = self.find(a);
+		let b = self.find(b);
+		self.parents[a] = b;
+	}
+}
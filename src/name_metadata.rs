@@ -0,0 +1,150 @@
+//! Meaning, language of origin and name days (Namenstage) of names, imported from a CSV file like this:
+//!
+//! ```csv
+//! name,meaning,origin,catholic_name_days,protestant_name_days
+//! Nikolaus,victory of the people,Greek,12-06,12-06
+//! Anna,grace,Hebrew,07-26,07-26
+//! Elisabeth,my God is an oath,Hebrew,11-19;07-08,11-19
+//! Mia,,,,
+//! ```
+//!
+//! Every column except `name` may be empty. Name days are written as `MM-DD`, multiple ones are separated by `;`.
+//! Importing a name again replaces all of its metadata.
+
+use crate::name_normalization::normalize_name;
+use anyhow::Context;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Clone, Debug)]
+pub struct NameMetadataRecord {
+	pub name: String,
+	pub meaning: Option<String>,
+	pub origin: Option<String>,
+	pub name_days: Vec<NameDay>,
+}
+
+#[derive(Deserialize)]
+struct RawNameMetadataRecord {
+	name: String,
+	meaning: Option<String>,
+	origin: Option<String>,
+	catholic_name_days: Option<String>,
+	protestant_name_days: Option<String>,
+}
+
+pub fn parse_metadata_csv(
+	path: &Path,
+) -> anyhow::Result<impl Iterator<Item = anyhow::Result<NameMetadataRecord>> + use<>> {
+	let reader = csv::Reader::from_path(path)?;
+	Ok(reader.into_deserialize().map(|result| {
+		let record: RawNameMetadataRecord = result?;
+		let name_days = [
+			(Calendar::Catholic, record.catholic_name_days),
+			(Calendar::Protestant, record.protestant_name_days),
+		]
+		.into_iter()
+		.flat_map(|(calendar, dates)| {
+			dates
+				.unwrap_or_default()
+				.split(';')
+				.map(str::trim)
+				.filter(|date| !date.is_empty())
+				.map(|date| NameDay::parse(calendar, date))
+				.collect::<Vec<_>>()
+		})
+		.collect::<anyhow::Result<_>>()
+		.with_context(|| format!("Invalid name day of '{}'", record.name))?;
+
+		Ok(NameMetadataRecord {
+			name: normalize_name(&record.name),
+			meaning: record.meaning.filter(|meaning| !meaning.trim().is_empty()),
+			origin: record.origin.filter(|origin| !origin.trim().is_empty()),
+			name_days,
+		})
+	}))
+}
+
+/// Name days differ between the Catholic and the Protestant calendar of saints
+#[derive(Clone, Copy, Debug, PartialEq, Eq, sqlx::Type, strum::Display)]
+#[sqlx(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Calendar {
+	Catholic,
+	Protestant,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NameDay {
+	pub calendar: Calendar,
+	pub month: u8,
+	pub day: u8,
+}
+
+impl NameDay {
+	/// Parses a date like `12-06`
+	pub fn parse(calendar: Calendar, date: &str) -> anyhow::Result<Self> {
+		let (month, day) = date
+			.split_once('-')
+			.with_context(|| format!("Expected a date like '12-06', got '{date}'"))?;
+		let month = month.parse()?;
+		let day = day.parse()?;
+		// leap year, so the 29th of February is valid as well
+		anyhow::ensure!(
+			NaiveDate::from_ymd_opt(2024, u32::from(month), u32::from(day)).is_some(),
+			"There is no {date} in the calendar"
+		);
+		Ok(Self { calendar, month, day })
+	}
+
+	fn date(self) -> Option<NaiveDate> {
+		NaiveDate::from_ymd_opt(2024, u32::from(self.month), u32::from(self.day))
+	}
+}
+
+impl Display for NameDay {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+		match self.date() {
+			Some(date) => write!(formatter, "{} ({})", date.format("%B %-d"), self.calendar),
+			None => write!(formatter, "{:02}-{:02} ({})", self.month, self.day, self.calendar),
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum Season {
+	Spring,
+	Summer,
+	#[strum(serialize = "autumn", serialize = "fall")]
+	Autumn,
+	Winter,
+}
+
+impl Season {
+	pub fn months(self) -> [u8; 3] {
+		match self {
+			Self::Spring => [3, 4, 5],
+			Self::Summer => [6, 7, 8],
+			Self::Autumn => [9, 10, 11],
+			Self::Winter => [12, 1, 2],
+		}
+	}
+}
+
+/// Months given as a season like `spring` or a month number like `3`
+pub fn parse_months(value: &str) -> Result<Vec<u8>, String> {
+	if let Ok(season) = Season::from_str(&value.to_lowercase()) {
+		return Ok(season.months().to_vec());
+	}
+
+	match value.parse() {
+		Ok(month @ 1..=12) => Ok(vec![month]),
+		_ => Err(format!(
+			"Expected a season (spring, summer, autumn, winter) or a month from 1 to 12, got '{value}'"
+		)),
+	}
+}
@@ -0,0 +1,31 @@
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+/// Canonical spelling that is used as the identity of a name.
+///
+/// Composes the name to NFC (so "Zoë" is the same no matter how the dataset encoded the "ë") and capitalizes every
+/// part of it, e.g. "ANNA-LENA" becomes "Anna-Lena" and "marie louise" becomes "Marie Louise".
+pub fn normalize_name(name: &str) -> String {
+	let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+
+	let mut normalized = String::with_capacity(name.len());
+	let mut previous_is_letter = false;
+	for character in name.nfc() {
+		if previous_is_letter {
+			normalized.extend(character.to_lowercase());
+		} else {
+			normalized.extend(character.to_uppercase());
+		}
+		previous_is_letter = character.is_alphabetic();
+	}
+	normalized
+}
+
+/// Key for case and diacritic insensitive matching, e.g. "Zoë", "ZOE" and "zoe" all become "zoe".
+pub fn fold_name(name: &str) -> String {
+	name.nfd()
+		.filter(|&character| !is_combining_mark(character))
+		.flat_map(char::to_lowercase)
+		.collect::<String>()
+		.replace('ß', "ss")
+}
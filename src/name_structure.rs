@@ -0,0 +1,155 @@
+use crate::name_normalization::fold_name;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, strum::Display, strum::EnumString, strum::VariantNames)]
+#[strum(serialize_all = "snake_case")]
+pub enum Language {
+	#[default]
+	German,
+	English,
+}
+
+#[derive(Clone, Debug, Default, clap::Args)]
+pub struct StructureFilter {
+	#[clap(long)]
+	pub min_letters: Option<usize>,
+	#[clap(long)]
+	pub max_letters: Option<usize>,
+	#[clap(long)]
+	pub min_syllables: Option<usize>,
+	#[clap(long)]
+	pub max_syllables: Option<usize>,
+	/// Language used for estimating the number of syllables
+	#[clap(long, default_value_t)]
+	pub language: Language,
+	/// Only names starting with one of these (comma separated), e.g. `--starts-with a,e`
+	#[clap(long, value_delimiter = ',')]
+	pub starts_with: Vec<String>,
+	/// Only names ending with one of these (comma separated), e.g. `--ends-with a,e,i,o,u`
+	#[clap(long, value_delimiter = ',')]
+	pub ends_with: Vec<String>,
+	/// Exclude names containing umlauts, accents or other non-ASCII letters
+	#[clap(long)]
+	pub without_diacritics: bool,
+}
+
+impl StructureFilter {
+	pub fn matches(&self, name: &str) -> bool {
+		let letters = letter_count(name);
+		if self.min_letters.is_some_and(|min| letters < min) || self.max_letters.is_some_and(|max| letters > max) {
+			return false;
+		}
+
+		if self.min_syllables.is_some() || self.max_syllables.is_some() {
+			let syllables = syllable_count(name, self.language);
+			if self.min_syllables.is_some_and(|min| syllables < min)
+				|| self.max_syllables.is_some_and(|max| syllables > max)
+			{
+				return false;
+			}
+		}
+
+		let folded_name = fold_name(name);
+		if !self.starts_with.is_empty()
+			&& !self
+				.starts_with
+				.iter()
+				.any(|prefix| folded_name.starts_with(&fold_name(prefix)))
+		{
+			return false;
+		}
+		if !self.ends_with.is_empty()
+			&& !self
+				.ends_with
+				.iter()
+				.any(|suffix| folded_name.ends_with(&fold_name(suffix)))
+		{
+			return false;
+		}
+
+		!(self.without_diacritics && has_diacritics(name))
+	}
+}
+
+pub fn letter_count(name: &str) -> usize {
+	name.chars().filter(|character| character.is_alphabetic()).count()
+}
+
+/// Umlauts, accents and other non-ASCII letters like `ß`
+pub fn has_diacritics(name: &str) -> bool {
+	name.chars()
+		.any(|character| character.is_alphabetic() && !character.is_ascii())
+}
+
+/// Estimates the number of syllables by counting groups of vowels.
+///
+/// This is only a heuristic, both languages have plenty of exceptions, but it is good enough for names.
+pub fn syllable_count(name: &str, language: Language) -> usize {
+	let characters = name.to_lowercase().chars().collect::<Vec<_>>();
+
+	let mut count = 0;
+	let mut index = 0;
+	while index < characters.len() {
+		if !is_vowel(&characters, index) {
+			index += 1;
+			continue;
+		}
+
+		let start = index;
+		while index < characters.len() && is_vowel(&characters, index) {
+			index += 1;
+		}
+		count += vowel_group_syllables(&characters[start..index], language);
+	}
+
+	if language == Language::English && count > 1 && has_silent_e(&characters) {
+		count -= 1;
+	}
+
+	count.max(1)
+}
+
+fn is_vowel(characters: &[char], index: usize) -> bool {
+	match characters[index] {
+		// a leading y followed by a vowel is a consonant, like in "Yvonne" or "Yannick"
+		'y' => !(index == 0 && characters.get(1).is_some_and(|&next| is_vowel_letter(next))),
+		character => is_vowel_letter(character),
+	}
+}
+
+fn is_vowel_letter(character: char) -> bool {
+	"aeiouäöüáàâéèêëíìîïóòôúùûy".contains(character)
+}
+
+/// Counts how many syllables a group of consecutive vowels contributes.
+fn vowel_group_syllables(group: &[char], language: Language) -> usize {
+	// sequences of vowels that are usually pronounced as a single syllable
+	const GERMAN_NUCLEI: &[&str] = &["ei", "ai", "au", "eu", "äu", "ie", "ey", "ay", "aa", "ee", "oo"];
+	const ENGLISH_NUCLEI: &[&str] = &[
+		"ai", "ay", "au", "aw", "ea", "ee", "ei", "ey", "ie", "oo", "ou", "oy", "ue",
+	];
+
+	let nuclei = match language {
+		Language::German => GERMAN_NUCLEI,
+		Language::English => ENGLISH_NUCLEI,
+	};
+
+	let mut count = 0;
+	let mut index = 0;
+	while index < group.len() {
+		let is_nucleus = group
+			.get(index..index + 2)
+			.is_some_and(|pair| nuclei.iter().any(|nucleus| nucleus.chars().eq(pair.iter().copied())));
+		index += if is_nucleus { 2 } else { 1 };
+		count += 1;
+	}
+	count
+}
+
+/// A trailing e after a consonant is usually silent in English, like in "Grace", "Kate" or "Michelle".
+fn has_silent_e(characters: &[char]) -> bool {
+	let [.., consonant, 'e'] = characters else {
+		return false;
+	};
+
+	consonant.is_alphabetic() && !is_vowel_letter(*consonant)
+}
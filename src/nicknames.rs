@@ -0,0 +1,145 @@
+//! Nicknames and diminutives, either imported from a CSV file with one nickname per line like this:
+//!
+//! ```csv
+//! name,nickname
+//! Elisabeth,Lisa
+//! Elisabeth,Sissi
+//! Johannes,Hannes
+//! ```
+//!
+//! or derived from the name with the usual German and English patterns, see [`derive_nicknames`].
+
+use crate::name_normalization::{fold_name, normalize_name};
+use crate::name_structure::{Language, syllable_count};
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct NicknameRecord {
+	pub name: String,
+	pub nickname: String,
+}
+
+pub fn parse_nicknames_csv(
+	path: &Path,
+) -> anyhow::Result<impl Iterator<Item = anyhow::Result<NicknameRecord>> + use<>> {
+	let reader = csv::Reader::from_path(path)?;
+	Ok(reader.into_deserialize().map(|result| {
+		let record: NicknameRecord = result?;
+		Ok(NicknameRecord {
+			name: normalize_name(&record.name),
+			nickname: normalize_name(&record.nickname),
+		})
+	}))
+}
+
+/// Short forms that the name is likely to end up as:
+/// - the first syllable, like "Max" for "Maximilian" or "Kath" for "Katharina"
+/// - diminutives of it, "-i" in German ("Maxi", "Kathi") and "-ie" and "-y" in English ("Kathie", "Kathy")
+/// - "-chen" for short German names, like "Annchen" for "Anna" or "Gretchen" for "Grete"
+///
+/// This only knows the regular patterns, irregular ones like "Lisa" for "Elisabeth" have to be imported.
+pub fn derive_nicknames(name: &str, language: Language) -> Vec<String> {
+	// only the first part of double names like "Anna-Lena"
+	let first_part = name.split(['-', ' ']).next().unwrap_or(name);
+	let characters = first_part.to_lowercase().chars().collect::<Vec<_>>();
+
+	let mut nicknames = Vec::new();
+	if let Some(stem) = first_syllable(&characters) {
+		// too short stems like "An" for "Anna" aren't used on their own
+		if stem.len() >= 3 && stem.len() + 2 <= characters.len() {
+			nicknames.push(stem.iter().collect::<String>());
+		}
+
+		if stem.last().is_some_and(|&last| !is_vowel(last)) {
+			let mut base = stem.iter().collect::<String>();
+			let single_vowel = match stem {
+				[vowel, _] => is_vowel(*vowel),
+				[.., before, vowel, _] => !is_vowel(*before) && is_vowel(*vowel),
+				_ => false,
+			};
+			if let Some(&consonant) = stem
+				.last()
+				.filter(|&&consonant| single_vowel && "bdglmnpst".contains(consonant))
+			{
+				// short vowels are kept short by doubling the consonant, like "Ben" -> "Benni"
+				base.push(consonant);
+			}
+
+			match language {
+				Language::German => nicknames.push(format!("{base}i")),
+				Language::English => {
+					nicknames.push(format!("{base}ie"));
+					nicknames.push(format!("{base}y"));
+				}
+			}
+		}
+	}
+
+	if language == Language::German && syllable_count(first_part, language) <= 2 {
+		nicknames.extend(chen_diminutive(&characters));
+	}
+
+	let folded_name = fold_name(name);
+	let mut unique_nicknames = Vec::<String>::new();
+	for nickname in nicknames {
+		let nickname = normalize_name(&nickname);
+		if fold_name(&nickname) != folded_name && !unique_nicknames.contains(&nickname) {
+			unique_nicknames.push(nickname);
+		}
+	}
+	unique_nicknames
+}
+
+/// Everything up to the first vowel (or vowel pair pronounced as one) and the consonant following it
+fn first_syllable(characters: &[char]) -> Option<&[char]> {
+	const NUCLEI: &[[char; 2]] = &[
+		['a', 'i'],
+		['a', 'u'],
+		['e', 'i'],
+		['e', 'u'],
+		['i', 'e'],
+		['e', 'y'],
+		['a', 'y'],
+		['o', 'u'],
+		['a', 'a'],
+		['e', 'e'],
+		['o', 'o'],
+	];
+
+	let vowel_start = characters.iter().position(|&character| is_vowel(character))?;
+	let mut vowel_end = vowel_start + 1;
+	if let Some(&[first, second]) = characters.get(vowel_start..vowel_start + 2) {
+		if NUCLEI.contains(&[first, second]) {
+			vowel_end += 1;
+		} else if is_vowel(second) {
+			// two separately pronounced vowels like in "Theodor", the syllable ends between them
+			return Some(&characters[..vowel_start + 2]);
+		}
+	}
+
+	let mut end = vowel_end;
+	match characters.get(end..end + 2) {
+		// a silent "h" only makes the vowel longer, like in "Johannes"
+		Some(['h', _]) => {}
+		Some(['c', 'h' | 'k'] | ['t' | 'p' | 's', 'h']) => end += 2,
+		Some([consonant, _]) if !is_vowel(*consonant) => end += 1,
+		_ => {}
+	}
+	Some(&characters[..end])
+}
+
+/// "-chen" replaces an unstressed "a" or "e" at the end, it doesn't go well with other vowels or hissing sounds
+fn chen_diminutive(characters: &[char]) -> Option<String> {
+	let stem = match characters {
+		[.., consonant, 'a' | 'e'] if !is_vowel(*consonant) => &characters[..characters.len() - 1],
+		[.., 'i', 'e'] => characters,
+		[.., last] if is_vowel(*last) || "sxzh".contains(*last) => return None,
+		_ => characters,
+	};
+	Some(format!("{}chen", stem.iter().collect::<String>()))
+}
+
+fn is_vowel(character: char) -> bool {
+	"aeiouäöüy".contains(character)
+}
@@ -0,0 +1,46 @@
+use rphonetic::{Cologne, DoubleMetaphone, Encoder};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, sqlx::Type, strum::AsRefStr)]
+#[sqlx(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum PhoneticAlgorithm {
+	/// Kölner Phonetik, made for German pronunciation
+	Cologne,
+	DoubleMetaphone,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhoneticCode {
+	pub algorithm: PhoneticAlgorithm,
+	pub code: String,
+}
+
+/// All phonetic codes of a name, two names sound alike if they share at least one of them.
+///
+/// Double Metaphone produces an alternate code for names with ambiguous pronunciation, both are included.
+pub fn phonetic_codes(name: &str) -> Vec<PhoneticCode> {
+	let double_metaphone = DoubleMetaphone::default().double_metaphone(name);
+
+	let mut codes = vec![
+		PhoneticCode {
+			algorithm: PhoneticAlgorithm::Cologne,
+			code: cologne_code(name),
+		},
+		PhoneticCode {
+			algorithm: PhoneticAlgorithm::DoubleMetaphone,
+			code: double_metaphone.primary(),
+		},
+		PhoneticCode {
+			algorithm: PhoneticAlgorithm::DoubleMetaphone,
+			code: double_metaphone.alternate(),
+		},
+	];
+	codes.retain(|PhoneticCode { code, .. }| !code.is_empty());
+	codes.dedup();
+	codes
+}
+
+/// Kölner Phonetik code, empty if the name doesn't contain any letters.
+pub fn cologne_code(name: &str) -> String {
+	Cologne.encode(name)
+}
@@ -0,0 +1,56 @@
+use std::fmt::{Display, Formatter};
+
+pub const CLASS_SIZE: u32 = 25;
+pub const SCHOOL_SIZE: u32 = 500;
+
+/// Fraction of all births in a dataset that got a given name.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct BirthShare(f64);
+
+impl BirthShare {
+	pub fn new(count: i64, total: i64) -> Option<Self> {
+		if total <= 0 {
+			return None;
+		}
+
+		#[expect(clippy::cast_precision_loss)]
+		Some(Self(count as f64 / total as f64))
+	}
+
+	pub fn expected_in(self, group_size: u32) -> f64 {
+		self.0 * f64::from(group_size)
+	}
+
+	pub fn percent(self) -> f64 {
+		self.0 * 100.0
+	}
+}
+
+impl Display for BirthShare {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(
+			formatter,
+			"≈{:.2} children in a class of {CLASS_SIZE}, ≈{:.1} in a school of {SCHOOL_SIZE}",
+			self.expected_in(CLASS_SIZE),
+			self.expected_in(SCHOOL_SIZE),
+		)
+	}
+}
+
+/// Datasets are usually published per year, so the source filename tends to contain it (e.g. `vornamen-2023.csv`).
+pub fn year_of_source(source: &str) -> Option<u16> {
+	source
+		.as_bytes()
+		.windows(4)
+		.enumerate()
+		.filter(|(index, window)| {
+			let before = index.checked_sub(1).map(|before| source.as_bytes()[before]);
+			let after = source.as_bytes().get(index + 4);
+			window.iter().all(u8::is_ascii_digit)
+				&& !before.is_some_and(|byte| byte.is_ascii_digit())
+				&& !after.is_some_and(u8::is_ascii_digit)
+		})
+		.filter_map(|(_, window)| std::str::from_utf8(window).ok()?.parse().ok())
+		.find(|year| (1800..2200).contains(year))
+}
@@ -0,0 +1,188 @@
+//! Export and import of the preferences, star ratings and notes of a project, to back them up or share them
+//! independent of the database. JSON keeps everything:
+//!
+//! ```json
+//! [
+//!   {
+//!     "name": "Anna",
+//!     "preference": "favorite",
+//!     "stars": 4,
+//!     "notes": [{ "voter": "Max", "note": "Just like grandma", "created_at": "2026-10-19 12:00:00" }]
+//!   }
+//! ]
+//! ```
+//!
+//! CSV has one line per name, multiple notes are separated by line breaks and lose their voter and time:
+//!
+//! ```csv
+//! name,preference,stars,notes
+//! Anna,favorite,4,Just like grandma
+//! Kevin,no_go,,
+//! ```
+//!
+//! An empty preference means undecided, names that are only in the file because of their notes.
+
+use crate::database::{MAXIMUM_STARS, NamePreference, Rating};
+use anyhow::Context;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// UTC
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum ExchangeFormat {
+	Csv,
+	Json,
+}
+
+impl ExchangeFormat {
+	/// JSON for `.json` files, CSV for everything else
+	pub fn from_path(path: &Path) -> Self {
+		match path.extension() {
+			Some(extension) if extension.eq_ignore_ascii_case("json") => Self::Json,
+			_ => Self::Csv,
+		}
+	}
+}
+
+/// What happens to names that already have a preference in the project
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ImportStrategy {
+	/// The file wins for every name in it, even an undecided name in the file resets the preference
+	Overwrite,
+	/// Names that already have a preference keep it, but still get the notes from the file
+	#[default]
+	KeepExisting,
+	/// Names that already have a preference are left alone completely
+	OnlyUndecided,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PreferenceRecord {
+	pub name: String,
+	#[serde(default)]
+	pub preference: Option<NamePreference>,
+	#[serde(default)]
+	pub stars: Option<u8>,
+	#[serde(default)]
+	pub notes: Vec<NoteRecord>,
+}
+
+impl PreferenceRecord {
+	pub fn rating(&self) -> Rating {
+		Rating {
+			preference: self.preference,
+			stars: self.preference.and(self.stars),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NoteRecord {
+	#[serde(default)]
+	pub voter: Option<String>,
+	pub note: String,
+	/// UTC, like `2026-10-19 12:00:00`
+	#[serde(default)]
+	pub created_at: Option<String>,
+}
+
+impl NoteRecord {
+	pub fn new(voter: Option<String>, note: String, created_at: NaiveDateTime) -> Self {
+		Self {
+			voter,
+			note,
+			created_at: Some(created_at.format(TIMESTAMP_FORMAT).to_string()),
+		}
+	}
+
+	pub fn created_at(&self) -> anyhow::Result<Option<NaiveDateTime>> {
+		self.created_at
+			.as_deref()
+			.map(|created_at| {
+				NaiveDateTime::parse_from_str(created_at, TIMESTAMP_FORMAT)
+					.with_context(|| format!("Expected a time like '2026-10-19 12:00:00', got '{created_at}'"))
+			})
+			.transpose()
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+struct CsvPreferenceRecord {
+	name: String,
+	preference: Option<NamePreference>,
+	stars: Option<u8>,
+	notes: Option<String>,
+}
+
+pub fn write_records(records: &[PreferenceRecord], format: ExchangeFormat, writer: impl Write) -> anyhow::Result<()> {
+	match format {
+		ExchangeFormat::Json => serde_json::to_writer_pretty(writer, records)?,
+		ExchangeFormat::Csv => {
+			let mut writer = csv::Writer::from_writer(writer);
+			for record in records {
+				writer.serialize(CsvPreferenceRecord {
+					name: record.name.clone(),
+					preference: record.preference,
+					stars: record.stars,
+					notes: (!record.notes.is_empty()).then(|| {
+						record
+							.notes
+							.iter()
+							.map(|note| note.note.as_str())
+							.collect::<Vec<_>>()
+							.join("\n")
+					}),
+				})?;
+			}
+			writer.flush()?;
+		}
+	}
+	Ok(())
+}
+
+pub fn read_records(path: &Path, format: ExchangeFormat) -> anyhow::Result<Vec<PreferenceRecord>> {
+	let records = match format {
+		ExchangeFormat::Json => serde_json::from_reader(File::open(path)?)?,
+		ExchangeFormat::Csv => csv::Reader::from_path(path)?
+			.into_deserialize()
+			.map(|result| {
+				let record: CsvPreferenceRecord = result?;
+				Ok(PreferenceRecord {
+					name: record.name,
+					preference: record.preference,
+					stars: record.stars,
+					notes: record
+						.notes
+						.unwrap_or_default()
+						.lines()
+						.map(str::trim)
+						.filter(|note| !note.is_empty())
+						.map(|note| NoteRecord {
+							voter: None,
+							note: note.to_owned(),
+							created_at: None,
+						})
+						.collect(),
+				})
+			})
+			.collect::<anyhow::Result<Vec<_>>>()?,
+	};
+
+	for record in &records {
+		if let Some(stars) = record.stars {
+			anyhow::ensure!(
+				(1..=MAXIMUM_STARS).contains(&stars),
+				"Invalid rating of '{}': {stars} stars, expected 1 to {MAXIMUM_STARS}",
+				record.name
+			);
+		}
+	}
+	Ok(records)
+}
@@ -0,0 +1,55 @@
+use crate::database::{MAXIMUM_STARS, NamePreference, Rating};
+
+/// How a conflict is resolved when a name has a different rating in both databases. Names that are only rated in one
+/// of them simply keep that rating.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum MergeStrategy {
+	/// The other database wins
+	Theirs,
+	/// This database wins
+	Ours,
+	/// A favorite in either database stays a favorite
+	#[default]
+	FavoriteWins,
+	/// A no-go in either database is a no-go
+	VetoWins,
+	/// This database wins, but the ratings of both databases are also kept separately for each voter
+	SeparateVoters,
+}
+
+impl MergeStrategy {
+	/// When both sides agree on the preference, the higher number of stars wins
+	pub fn resolve(self, ours: Rating, theirs: Rating) -> Rating {
+		use MergeStrategy::*;
+		match self {
+			Theirs => theirs,
+			Ours | SeparateVoters => ours,
+			FavoriteWins => prefer(NamePreference::Favorite, ours, theirs),
+			VetoWins => prefer(NamePreference::NoGo, ours, theirs),
+		}
+	}
+}
+
+fn prefer(preference: NamePreference, ours: Rating, theirs: Rating) -> Rating {
+	let sides = [ours, theirs];
+	let preference = if sides.iter().any(|side| side.preference == Some(preference)) {
+		Some(preference)
+	} else {
+		ours.preference
+	};
+	let stars = sides
+		.iter()
+		.filter(|side| side.preference == preference)
+		.map(|side| match side {
+			// favorites that were never rated count as fully rated
+			Rating {
+				preference: Some(NamePreference::Favorite),
+				stars: None,
+			} => Some(MAXIMUM_STARS),
+			Rating { stars, .. } => *stars,
+		})
+		.max()
+		.flatten();
+	Rating { preference, stars }
+}
@@ -0,0 +1,413 @@
+//! Small query language for searching names, e.g. `len:<6 starts:a gender:female pref:undecided pop:>100 -contains:ie`,
+//! `origin:greek nameday:spring` or `stars:>=4`.
+//!
+//! Every whitespace separated term has to match. Plain words are substring searches, `key:value` terms filter
+//! by a property of the name and a leading `-` negates a term. Values containing spaces can be put in double quotes.
+
+use crate::csv_parser::Gender;
+use crate::database::NamePreference;
+use crate::name_metadata::parse_months;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+	pub terms: Vec<Term>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Term {
+	pub negated: bool,
+	pub predicate: Predicate,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Predicate {
+	Contains(String),
+	/// Like [`Predicate::Contains`], but without searching the notes
+	NameContains(String),
+	StartsWith(String),
+	EndsWith(String),
+	Length(Comparison),
+	/// Total number of births with this name across all sources
+	Popularity(Comparison),
+	/// `female` and `male` include names used for both genders, `both` only matches those.
+	Gender(Gender),
+	/// `None` means undecided
+	Preference(Option<NamePreference>),
+	/// Star rating in the project, favorites without stars count as 5 stars and everything else as 0 stars
+	Stars(Comparison),
+	/// Substring of the language of origin from the imported metadata
+	Origin(String),
+	/// Substring of the meaning from the imported metadata
+	Meaning(String),
+	/// Names with this tag in the project, case insensitive
+	Tag(String),
+	/// Months with a name day in any calendar, written as a season like `spring` or a month number like `3`
+	NameDay(Vec<u8>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Comparison {
+	pub operator: ComparisonOperator,
+	pub value: i64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComparisonOperator {
+	Less,
+	LessOrEqual,
+	Equal,
+	GreaterOrEqual,
+	Greater,
+}
+
+impl ComparisonOperator {
+	pub fn as_sql(self) -> &'static str {
+		use ComparisonOperator::*;
+		match self {
+			Less => "<",
+			LessOrEqual => "<=",
+			Equal => "=",
+			GreaterOrEqual => ">=",
+			Greater => ">",
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuerySyntaxError {
+	/// Character offset into the query where the error was found
+	pub position: usize,
+	pub message: String,
+}
+
+impl Display for QuerySyntaxError {
+	fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(formatter, "{} (at character {})", self.message, self.position + 1)
+	}
+}
+
+impl std::error::Error for QuerySyntaxError {}
+
+const KEYS: &str = "contains, starts, ends, len, pop, gender, pref, stars, tag, origin, meaning, nameday";
+
+impl SearchQuery {
+	pub fn parse(query: &str) -> Result<Self, QuerySyntaxError> {
+		let terms = tokenize(query)?
+			.into_iter()
+			.map(|token| parse_term(&token))
+			.collect::<Result<_, _>>()?;
+		Ok(Self { terms })
+	}
+}
+
+impl FromStr for SearchQuery {
+	type Err = QuerySyntaxError;
+
+	fn from_str(query: &str) -> Result<Self, Self::Err> {
+		Self::parse(query)
+	}
+}
+
+struct Token {
+	position: usize,
+	negated: bool,
+	key: Option<String>,
+	value: String,
+	value_position: usize,
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, QuerySyntaxError> {
+	let mut tokens = Vec::new();
+	let mut characters = query.chars().enumerate().peekable();
+
+	while let Some(&(position, character)) = characters.peek() {
+		if character.is_whitespace() {
+			characters.next();
+			continue;
+		}
+
+		let negated = character == '-';
+		if negated {
+			characters.next();
+		}
+
+		let mut key = None;
+		let mut value = String::new();
+		let mut value_position = characters.peek().map_or(position + 1, |&(position, _)| position);
+		let mut quote_start = None;
+		while let Some(&(position, character)) = characters.peek() {
+			match character {
+				'"' => {
+					quote_start = match quote_start {
+						Some(_) => None,
+						None => Some(position),
+					};
+				}
+				':' if key.is_none() && quote_start.is_none() => {
+					key = Some(std::mem::take(&mut value));
+					value_position = position + 1;
+				}
+				character if character.is_whitespace() && quote_start.is_none() => break,
+				character => value.push(character),
+			}
+			characters.next();
+		}
+
+		if let Some(position) = quote_start {
+			return Err(QuerySyntaxError {
+				position,
+				message: "Unterminated quote".to_owned(),
+			});
+		}
+
+		if value.is_empty() {
+			return Err(QuerySyntaxError {
+				position: value_position,
+				message: match &key {
+					Some(key) => format!("Missing value after '{key}:'"),
+					None => "Missing search term after '-'".to_owned(),
+				},
+			});
+		}
+
+		tokens.push(Token {
+			position,
+			negated,
+			key,
+			value,
+			value_position,
+		});
+	}
+
+	Ok(tokens)
+}
+
+fn parse_term(token: &Token) -> Result<Term, QuerySyntaxError> {
+	let error = |message: String| QuerySyntaxError {
+		position: token.value_position,
+		message,
+	};
+
+	let value = token.value.clone();
+	let predicate = match token.key.as_deref() {
+		None | Some("contains") => Predicate::Contains(value),
+		Some("starts") => Predicate::StartsWith(value),
+		Some("ends") => Predicate::EndsWith(value),
+		Some("len") => Predicate::Length(parse_comparison(&value).map_err(error)?),
+		Some("pop") => Predicate::Popularity(parse_comparison(&value).map_err(error)?),
+		Some("gender") => Predicate::Gender(
+			value
+				.to_lowercase()
+				.parse()
+				.map_err(|_| error(format!("Unknown gender '{value}', expected female, male or both")))?,
+		),
+		Some("pref") => Predicate::Preference(match value.to_lowercase().as_str() {
+			"favorite" | "fav" => Some(NamePreference::Favorite),
+			"nogo" | "no_go" => Some(NamePreference::NoGo),
+			"undecided" | "none" => None,
+			_ => {
+				return Err(error(format!(
+					"Unknown preference '{value}', expected favorite, nogo or undecided"
+				)));
+			}
+		}),
+		Some("stars") => Predicate::Stars(parse_comparison(&value).map_err(error)?),
+		Some("tag") => Predicate::Tag(value),
+		Some("origin") => Predicate::Origin(value),
+		Some("meaning") => Predicate::Meaning(value),
+		Some("nameday") => Predicate::NameDay(parse_months(&value).map_err(error)?),
+		Some(key) => {
+			return Err(QuerySyntaxError {
+				position: token.position + usize::from(token.negated),
+				message: format!("Unknown filter '{key}', expected one of {KEYS}"),
+			});
+		}
+	};
+
+	Ok(Term {
+		negated: token.negated,
+		predicate,
+	})
+}
+
+fn parse_comparison(value: &str) -> Result<Comparison, String> {
+	use ComparisonOperator::*;
+
+	let (operator, number) = [
+		("<=", LessOrEqual),
+		(">=", GreaterOrEqual),
+		("<", Less),
+		(">", Greater),
+		("=", Equal),
+	]
+	.into_iter()
+	.find_map(|(prefix, operator)| Some((operator, value.strip_prefix(prefix)?)))
+	.unwrap_or((Equal, value));
+
+	let value = number
+		.parse()
+		.map_err(|_| format!("Expected a number like '5', '<5' or '>=5', got '{value}'"))?;
+	Ok(Comparison { operator, value })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn term(predicate: Predicate) -> Term {
+		Term {
+			negated: false,
+			predicate,
+		}
+	}
+
+	fn parse_error(query: &str) -> QuerySyntaxError {
+		SearchQuery::parse(query).expect_err("query should be rejected")
+	}
+
+	#[test]
+	fn empty_query_has_no_terms() {
+		assert_eq!(SearchQuery::parse("  ").unwrap(), SearchQuery::default());
+	}
+
+	#[test]
+	fn plain_words_are_substring_searches() {
+		let query = SearchQuery::parse("an contains:ie").unwrap();
+		assert_eq!(
+			query.terms,
+			vec![
+				term(Predicate::Contains("an".to_owned())),
+				term(Predicate::Contains("ie".to_owned())),
+			]
+		);
+	}
+
+	#[test]
+	fn parses_every_key() {
+		let query = SearchQuery::parse(
+			"starts:a ends:a len:5 pop:>100 gender:FEMALE pref:fav stars:>=4 tag:short origin:greek meaning:light \
+			 nameday:spring",
+		)
+		.unwrap();
+		assert_eq!(
+			query.terms,
+			vec![
+				term(Predicate::StartsWith("a".to_owned())),
+				term(Predicate::EndsWith("a".to_owned())),
+				term(Predicate::Length(Comparison {
+					operator: ComparisonOperator::Equal,
+					value: 5
+				})),
+				term(Predicate::Popularity(Comparison {
+					operator: ComparisonOperator::Greater,
+					value: 100
+				})),
+				term(Predicate::Gender(Gender::Female)),
+				term(Predicate::Preference(Some(NamePreference::Favorite))),
+				term(Predicate::Stars(Comparison {
+					operator: ComparisonOperator::GreaterOrEqual,
+					value: 4
+				})),
+				term(Predicate::Tag("short".to_owned())),
+				term(Predicate::Origin("greek".to_owned())),
+				term(Predicate::Meaning("light".to_owned())),
+				term(Predicate::NameDay(vec![3, 4, 5])),
+			]
+		);
+	}
+
+	#[test]
+	fn parses_undecided_and_no_go_preferences() {
+		let query = SearchQuery::parse("pref:undecided pref:nogo").unwrap();
+		assert_eq!(
+			query.terms,
+			vec![
+				term(Predicate::Preference(None)),
+				term(Predicate::Preference(Some(NamePreference::NoGo))),
+			]
+		);
+	}
+
+	#[test]
+	fn parses_comparison_prefixes() {
+		for (value, operator) in [
+			("<5", ComparisonOperator::Less),
+			("<=5", ComparisonOperator::LessOrEqual),
+			("=5", ComparisonOperator::Equal),
+			("5", ComparisonOperator::Equal),
+			(">=5", ComparisonOperator::GreaterOrEqual),
+			(">5", ComparisonOperator::Greater),
+		] {
+			assert_eq!(
+				parse_comparison(value),
+				Ok(Comparison { operator, value: 5 }),
+				"comparison '{value}'"
+			);
+		}
+		assert!(parse_comparison(">>5").is_err());
+		assert!(parse_comparison("five").is_err());
+	}
+
+	#[test]
+	fn quoted_values_keep_spaces_and_colons() {
+		let query = SearchQuery::parse(r#"meaning:"gift of god" "a:b""#).unwrap();
+		assert_eq!(
+			query.terms,
+			vec![
+				term(Predicate::Meaning("gift of god".to_owned())),
+				term(Predicate::Contains("a:b".to_owned())),
+			]
+		);
+	}
+
+	#[test]
+	fn leading_dash_negates_a_term() {
+		let query = SearchQuery::parse("-contains:ie -x").unwrap();
+		assert_eq!(
+			query.terms,
+			vec![
+				Term {
+					negated: true,
+					predicate: Predicate::Contains("ie".to_owned()),
+				},
+				Term {
+					negated: true,
+					predicate: Predicate::Contains("x".to_owned()),
+				},
+			]
+		);
+	}
+
+	#[test]
+	fn rejects_unterminated_quote() {
+		let error = parse_error(r#"len:5 meaning:"gift of"#);
+		assert_eq!(error.message, "Unterminated quote");
+		assert_eq!(error.position, 14);
+	}
+
+	#[test]
+	fn rejects_unknown_key() {
+		let error = parse_error("len:5 -color:red");
+		assert!(error.message.starts_with("Unknown filter 'color'"), "{error}");
+		assert_eq!(error.position, 7);
+	}
+
+	#[test]
+	fn rejects_missing_values() {
+		assert_eq!(parse_error("tag:").message, "Missing value after 'tag:'");
+		assert_eq!(parse_error("a -").message, "Missing search term after '-'");
+	}
+
+	#[test]
+	fn rejects_invalid_values() {
+		let error = parse_error("pop:many");
+		assert!(error.message.starts_with("Expected a number"), "{error}");
+		assert_eq!(error.position, 4);
+
+		assert!(parse_error("gender:other").message.starts_with("Unknown gender"));
+		assert!(parse_error("pref:maybe").message.starts_with("Unknown preference"));
+		assert!(parse_error("nameday:13").message.starts_with("Expected a season"));
+	}
+}
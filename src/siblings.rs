@@ -0,0 +1,112 @@
+//! How well a name fits the names of older siblings.
+
+use crate::name_groups::are_variants;
+use crate::name_normalization::fold_name;
+use crate::name_structure::{Language, letter_count, syllable_count};
+use crate::phonetics::PhoneticCode;
+use crate::surname::{alliteration, rhymes};
+
+/// Names whose popularity peaked further apart than this belong to different generations
+const MAXIMUM_ERA_DIFFERENCE: u16 = 25;
+/// Names that differ more in length than this feel unbalanced next to each other, like "Max" and "Maximiliane"
+const MAXIMUM_LETTER_DIFFERENCE: usize = 4;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SiblingHarmony {
+	/// Both names start with the same letter, which mixes up mail and labeled belongings
+	pub same_initial: bool,
+	/// Both names end the same way, like "Jana" and "Hanna"
+	pub rhyme: bool,
+	/// The names are easily confused when calling across the playground, see [`too_similar`]
+	pub too_similar: bool,
+	pub letter_difference: usize,
+	pub syllable_difference: usize,
+	/// Years between the average birth years of both names, if both are in the datasets
+	pub era_difference: Option<u16>,
+}
+
+impl SiblingHarmony {
+	/// `candidate_era` and `sibling_era` are the average birth years of the names, see
+	/// [`crate::database::views::read_popularity_era`], the phonetic codes are the stored ones, see
+	/// [`crate::database::views::read_phonetic_codes`]
+	pub fn between(
+		candidate: &str,
+		candidate_codes: &[PhoneticCode],
+		sibling: &str,
+		sibling_codes: &[PhoneticCode],
+		candidate_era: Option<u16>,
+		sibling_era: Option<u16>,
+		language: Language,
+	) -> Self {
+		let folded_candidate = fold_name(candidate);
+		let folded_sibling = fold_name(sibling);
+
+		Self {
+			same_initial: alliteration(&folded_candidate, &folded_sibling),
+			rhyme: rhymes(&folded_candidate, &folded_sibling),
+			too_similar: too_similar(candidate, candidate_codes, sibling, sibling_codes),
+			letter_difference: letter_count(candidate).abs_diff(letter_count(sibling)),
+			syllable_difference: syllable_count(candidate, language).abs_diff(syllable_count(sibling, language)),
+			era_difference: candidate_era.zip(sibling_era).map(|(a, b)| a.abs_diff(b)),
+		}
+	}
+
+	/// Matching style: the names were popular at around the same time and have a similar length
+	pub fn similar_style(&self) -> bool {
+		self.era_difference
+			.is_none_or(|difference| difference <= MAXIMUM_ERA_DIFFERENCE)
+			&& self.letter_difference <= MAXIMUM_LETTER_DIFFERENCE
+			&& self.syllable_difference <= 1
+	}
+
+	/// From 0 (lots of issues) to 100 (no issues at all)
+	pub fn score(&self) -> u8 {
+		let penalties = [
+			(self.too_similar, 40),
+			(self.rhyme, 20),
+			(self.same_initial, 10),
+			(!self.similar_style(), 15),
+		];
+		penalties
+			.into_iter()
+			.filter(|(applies, _)| *applies)
+			.fold(100u8, |score, (_, penalty)| score.saturating_sub(penalty))
+	}
+
+	/// Human readable description of everything that doesn't fit well
+	pub fn issues(&self) -> Vec<String> {
+		let mut issues = Vec::new();
+		if self.too_similar {
+			issues.push("Too similar: easily confused when calling out".to_owned());
+		}
+		if self.rhyme {
+			issues.push("Rhyme: both names end the same way".to_owned());
+		}
+		if self.same_initial {
+			issues.push("Same initial letter".to_owned());
+		}
+		if let Some(era_difference) = self
+			.era_difference
+			.filter(|&difference| difference > MAXIMUM_ERA_DIFFERENCE)
+		{
+			issues.push(format!("Different era: popular {era_difference} years apart"));
+		}
+		if self.letter_difference > MAXIMUM_LETTER_DIFFERENCE || self.syllable_difference > 1 {
+			issues.push(format!(
+				"Different length: {} letters and {} syllables apart",
+				self.letter_difference, self.syllable_difference
+			));
+		}
+		issues
+	}
+}
+
+/// Names that sound alike or are only a letter or two apart, like "Mia" and "Lia" or "Lena" and "Lina".
+pub fn too_similar(
+	candidate: &str,
+	candidate_codes: &[PhoneticCode],
+	sibling: &str,
+	sibling_codes: &[PhoneticCode],
+) -> bool {
+	candidate_codes.iter().any(|code| sibling_codes.contains(code)) || are_variants(candidate, sibling)
+}
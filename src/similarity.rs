@@ -0,0 +1,83 @@
+/// Number of single character insertions, deletions or substitutions to get from one name to the other,
+/// ignoring case.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+	let a = a.to_lowercase().chars().collect::<Vec<_>>();
+	let b = b.to_lowercase().chars().collect::<Vec<_>>();
+
+	let mut previous_row = (0..=b.len()).collect::<Vec<_>>();
+	let mut current_row = vec![0; b.len() + 1];
+	for (i, a_character) in a.iter().enumerate() {
+		current_row[0] = i + 1;
+		for (j, b_character) in b.iter().enumerate() {
+			let substitution_cost = usize::from(a_character != b_character);
+			current_row[j + 1] = (previous_row[j] + substitution_cost)
+				.min(previous_row[j + 1] + 1)
+				.min(current_row[j] + 1);
+		}
+		std::mem::swap(&mut previous_row, &mut current_row);
+	}
+
+	previous_row[b.len()]
+}
+
+/// Lowercase, overlapping three character windows, the same way the trigram tokenizer of the full text index splits text.
+pub fn trigrams(text: &str) -> Vec<String> {
+	let characters = text.to_lowercase().chars().collect::<Vec<_>>();
+	let mut trigrams = characters
+		.windows(3)
+		.map(|window| window.iter().collect::<String>())
+		.collect::<Vec<_>>();
+	trigrams.sort();
+	trigrams.dedup();
+	trigrams
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Similarity {
+	pub levenshtein: usize,
+	pub longest_common_substring: usize,
+	/// Length of the longest common substring relative to the average length of both names, from 0 to 1
+	pub longest_common_substring_ratio: f64,
+}
+
+impl Similarity {
+	pub fn between(a: &str, b: &str) -> Self {
+		let longest_common_substring = longest_common_substring(a, b);
+		let total_length = a.chars().count() + b.chars().count();
+		#[expect(clippy::cast_precision_loss)]
+		let longest_common_substring_ratio = if total_length == 0 {
+			1.0
+		} else {
+			(2 * longest_common_substring) as f64 / total_length as f64
+		};
+
+		Self {
+			levenshtein: levenshtein(a, b),
+			longest_common_substring,
+			longest_common_substring_ratio,
+		}
+	}
+}
+
+/// Length of the longest run of characters both names have in common, ignoring case.
+pub fn longest_common_substring(a: &str, b: &str) -> usize {
+	let a = a.to_lowercase().chars().collect::<Vec<_>>();
+	let b = b.to_lowercase().chars().collect::<Vec<_>>();
+
+	let mut longest = 0;
+	let mut previous_row = vec![0; b.len() + 1];
+	let mut current_row = vec![0; b.len() + 1];
+	for a_character in &a {
+		for (j, b_character) in b.iter().enumerate() {
+			current_row[j + 1] = if a_character == b_character {
+				previous_row[j] + 1
+			} else {
+				0
+			};
+			longest = longest.max(current_row[j + 1]);
+		}
+		std::mem::swap(&mut previous_row, &mut current_row);
+	}
+
+	longest
+}
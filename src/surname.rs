@@ -0,0 +1,138 @@
+//! How well a first name goes together with the family surname when said out loud.
+
+use crate::initials::{InitialsBlocklist, dotted_initials, initials};
+use crate::name_normalization::fold_name;
+use crate::name_structure::{Language, letter_count, syllable_count};
+
+/// Names longer than this are tedious to say and write, e.g. on every form
+pub const MAXIMUM_COMBINED_LETTERS: usize = 22;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SurnameCompatibility {
+	/// Both names start with the same letter, like "Max Müller"
+	pub alliteration: bool,
+	/// The first name ends and the surname starts with a vowel, so they run into each other, like "Anna Albers"
+	pub vowel_clash: bool,
+	/// Both names end the same way, like "Jan Mann" or "Lena Jena"
+	pub rhyme: bool,
+	pub first_name_syllables: usize,
+	pub surname_syllables: usize,
+	pub combined_letters: usize,
+	/// Initials like "K.Z." that are on the bundled blocklist, usually because they are a common abbreviation
+	pub awkward_initials: Option<String>,
+}
+
+impl SurnameCompatibility {
+	pub fn between(first_name: &str, surname: &str, language: Language) -> Self {
+		let folded_first_name = fold_name(first_name);
+		let folded_surname = fold_name(surname);
+
+		let names = [first_name, surname];
+		let awkward_initials = InitialsBlocklist::bundled()
+			.contains(&initials(&names))
+			.then(|| dotted_initials(&names));
+
+		Self {
+			alliteration: alliteration(&folded_first_name, &folded_surname),
+			vowel_clash: vowel_clash(&folded_first_name, &folded_surname),
+			rhyme: rhymes(&folded_first_name, &folded_surname),
+			first_name_syllables: syllable_count(first_name, language),
+			surname_syllables: syllable_count(surname, language),
+			combined_letters: letter_count(first_name) + letter_count(surname),
+			awkward_initials,
+		}
+	}
+
+	/// Two single syllable names sound choppy, like "Max Kurz"
+	pub fn choppy_rhythm(&self) -> bool {
+		self.first_name_syllables == 1 && self.surname_syllables == 1
+	}
+
+	pub fn too_long(&self) -> bool {
+		self.combined_letters > MAXIMUM_COMBINED_LETTERS
+	}
+
+	/// From 0 (lots of issues) to 100 (no issues at all)
+	pub fn score(&self) -> u8 {
+		let penalties = [
+			(self.alliteration, 10),
+			(self.vowel_clash, 20),
+			(self.rhyme, 30),
+			(self.choppy_rhythm(), 15),
+			(self.too_long(), 15),
+			(self.awkward_initials.is_some(), 30),
+		];
+		penalties
+			.into_iter()
+			.filter(|(applies, _)| *applies)
+			.fold(100u8, |score, (_, penalty)| score.saturating_sub(penalty))
+	}
+
+	/// Human readable description of everything that doesn't fit well
+	pub fn issues(&self) -> Vec<String> {
+		let mut issues = Vec::new();
+		if self.alliteration {
+			issues.push("Alliteration: both names start with the same letter".to_owned());
+		}
+		if self.vowel_clash {
+			issues.push("Vowel clash: the names run into each other".to_owned());
+		}
+		if self.rhyme {
+			issues.push("Rhyme: both names end the same way".to_owned());
+		}
+		if self.choppy_rhythm() {
+			issues.push("Choppy rhythm: both names only have one syllable".to_owned());
+		}
+		if self.too_long() {
+			issues.push(format!("Long: {} letters combined", self.combined_letters));
+		}
+		if let Some(initials) = &self.awkward_initials {
+			issues.push(format!("Awkward initials: {initials}"));
+		}
+		issues
+	}
+}
+
+fn is_vowel(character: char) -> bool {
+	"aeiouy".contains(character)
+}
+
+/// Both names start with the same letter, expects names folded with [`fold_name`]
+pub fn alliteration(folded_first: &str, folded_second: &str) -> bool {
+	folded_first.chars().next() == folded_second.chars().next()
+}
+
+/// The first name ends and the second one starts with a vowel, expects names folded with [`fold_name`]
+pub fn vowel_clash(folded_first: &str, folded_second: &str) -> bool {
+	folded_first.chars().next_back().is_some_and(is_vowel) && folded_second.chars().next().is_some_and(is_vowel)
+}
+
+/// Compares the part of the names that rhymes: Everything from the last vowel on, or from the second to last vowel
+/// if the name ends with a vowel (like "-ena" in "Lena"). Doubled letters are ignored, so "Jan" rhymes with "Mann".
+pub fn rhymes(folded_first_name: &str, folded_surname: &str) -> bool {
+	let first_name_ending = rhyming_part(folded_first_name);
+	rhyming_part(folded_surname) == first_name_ending && first_name_ending.chars().count() >= 2
+}
+
+fn rhyming_part(folded_name: &str) -> String {
+	let mut characters = folded_name
+		.chars()
+		.filter(|character| character.is_alphabetic())
+		.collect::<Vec<_>>();
+	characters.dedup();
+
+	let vowel_group_starts = (0..characters.len())
+		.filter(|&index| is_vowel(characters[index]) && (index == 0 || !is_vowel(characters[index - 1])))
+		.collect::<Vec<_>>();
+	let groups_to_include = if characters.last().copied().is_some_and(is_vowel) {
+		2
+	} else {
+		1
+	};
+
+	let start = vowel_group_starts
+		.len()
+		.checked_sub(groups_to_include)
+		.map_or(0, |index| vowel_group_starts[index]);
+	characters[start..].iter().collect()
+}
@@ -0,0 +1,41 @@
+//! Sync of preferences between copies of the database without a server. Every preference change is recorded as an
+//! operation with a Lamport clock, `sync export` writes the operations since the last export to a JSON file and
+//! `sync import` applies the operations from the file of another copy. Of all operations on the same name, the one with
+//! the highest clock wins (the device id breaks ties), so all copies end up with the same preferences no matter in
+//! which order the files are imported. Operations that were imported from one copy are passed on to the next.
+
+use crate::database::NamePreference;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Changeset {
+	/// The database that exported the changeset
+	pub device_id: String,
+	pub operations: Vec<SyncOperation>,
+}
+
+/// A preference change, identified by the device it was made on and its clock
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SyncOperation {
+	pub device_id: String,
+	pub clock: i64,
+	/// Project ids differ between databases, their names don't
+	pub project: String,
+	pub name: String,
+	/// `None` means undecided
+	pub preference: Option<NamePreference>,
+	pub stars: Option<u8>,
+	pub voter: Option<String>,
+}
+
+pub fn write_changeset(changeset: &Changeset, path: &Path) -> anyhow::Result<()> {
+	serde_json::to_writer_pretty(BufWriter::new(File::create(path)?), changeset)?;
+	Ok(())
+}
+
+pub fn read_changeset(path: &Path) -> anyhow::Result<Changeset> {
+	Ok(serde_json::from_reader(File::open(path)?)?)
+}